@@ -0,0 +1,19 @@
+//! Arbitrary input must never panic the parsing and validation, only return errors.
+//! Run with `cargo fuzz run parse_mnemonic`, from the repository root.
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use wallet_rs::bip39::{self, Mnemonic, ParseMode};
+
+fuzz_target!(|data: &[u8]| {
+    let phrase = String::from_utf8_lossy(data);
+    let mnemonics: Vec<String> = phrase.split_whitespace().map(String::from).collect();
+
+    let _ = bip39::checksum_status(&mnemonics);
+    let _ = bip39::is_checksum_valid(&mnemonics);
+    let _ = bip39::decompose(&mnemonics);
+    let _ = bip39::parse_mnemonics(&phrase, ParseMode::Strict);
+    let _ = bip39::parse_mnemonics(&phrase, ParseMode::Lenient);
+    let _ = bip39::parse_and_fix(&phrase);
+    let _ = phrase.parse::<Mnemonic>();
+    let _ = Mnemonic::from_phrase(&phrase);
+});
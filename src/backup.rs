@@ -0,0 +1,149 @@
+//! Mnemonics encrypted with a password, to be stored e.g. on a USB stick.
+//!
+//! Key is derived from the password with Argon2id, phrase is encrypted with XChaCha20-Poly1305.
+//! Blob layout, version 1:
+//! `magic (4) | version (1) | salt (16) | nonce (24) | ciphertext with tag`.
+//! Magic and version are authenticated as associated data.
+use crate::bip39::{Bip39Error, Mnemonic};
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, Payload};
+use chacha20poly1305::{KeyInit, XChaCha20Poly1305, XNonce};
+use rand::{thread_rng, RngCore};
+use zeroize::Zeroizing;
+
+const MAGIC: &[u8; 4] = b"WRSB";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = MAGIC.len() + 1;
+
+/// Derives encryption key from the password, Argon2id with default parameters
+fn derive_key(password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, Bip39Error> {
+    let mut key = Zeroizing::new([0; 32]);
+
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut *key)
+        .map_err(|e| Bip39Error::Internal(e.to_string()))?;
+
+    Ok(key)
+}
+
+/// Encrypts mnemonic with the password, returns versioned blob, see module docs.
+/// Salt and nonce are random, so the same mnemonic gives a different blob every time.
+/// Takes `Mnemonic`, so a phrase with a typo never ends up in a backup it cannot be restored from.
+pub fn encrypt_mnemonic(mnemonic: &Mnemonic, password: &str) -> Result<Vec<u8>, Bip39Error> {
+    let mut salt = [0; SALT_LEN];
+    let mut nonce = [0; NONCE_LEN];
+    thread_rng().fill_bytes(&mut salt);
+    thread_rng().fill_bytes(&mut nonce);
+
+    let key = derive_key(password, &salt)?;
+    let cipher = XChaCha20Poly1305::new(key.as_ref().into());
+
+    let mut blob = Vec::with_capacity(HEADER_LEN + SALT_LEN + NONCE_LEN);
+    blob.extend_from_slice(MAGIC);
+    blob.push(VERSION);
+
+    let phrase = Zeroizing::new(mnemonic.words().join(" "));
+    let ciphertext = cipher
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: phrase.as_bytes(),
+                aad: &blob,
+            },
+        )
+        .map_err(|e| Bip39Error::Internal(e.to_string()))?;
+
+    blob.extend_from_slice(&salt);
+    blob.extend_from_slice(&nonce);
+    blob.extend_from_slice(&ciphertext);
+
+    Ok(blob)
+}
+
+/// Decrypts blob made by `encrypt_mnemonic`. Wrong password and tampered blob
+/// cannot be told apart, both give `Bip39Error::DecryptionFailed`.
+pub fn decrypt_mnemonic(blob: &[u8], password: &str) -> Result<Mnemonic, Bip39Error> {
+    if blob.len() < HEADER_LEN + SALT_LEN + NONCE_LEN {
+        return Err(Bip39Error::InvalidBackup("too short".to_string()));
+    }
+    let (header, rest) = blob.split_at(HEADER_LEN);
+    let (salt, rest) = rest.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    if &header[..MAGIC.len()] != MAGIC {
+        return Err(Bip39Error::InvalidBackup("unknown format".to_string()));
+    }
+    if header[MAGIC.len()] != VERSION {
+        return Err(Bip39Error::InvalidBackup(format!(
+            "unsupported version {}",
+            header[MAGIC.len()]
+        )));
+    }
+
+    let key = derive_key(password, salt)?;
+    let cipher = XChaCha20Poly1305::new(key.as_ref().into());
+
+    let phrase = Zeroizing::new(
+        cipher
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: header,
+                },
+            )
+            .map_err(|_| Bip39Error::DecryptionFailed)?,
+    );
+
+    let phrase = std::str::from_utf8(&phrase)
+        .map_err(|_| Bip39Error::InvalidBackup("phrase is not UTF-8".to_string()))?;
+
+    match Mnemonic::from_phrase(phrase) {
+        Err(Bip39Error::InvalidChecksum) => {
+            Err(Bip39Error::InvalidBackup("invalid checksum".to_string()))
+        }
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mnemonics() -> Mnemonic {
+        "stick cluster blood sad onion age laptop grab cement unknown yard spend"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn encrypted_backup_round_trip() {
+        let blob = encrypt_mnemonic(&mnemonics(), "correct horse").unwrap();
+
+        assert_eq!(MAGIC, &blob[..4]);
+        assert_eq!(Ok(mnemonics()), decrypt_mnemonic(&blob, "correct horse"));
+    }
+
+    #[test]
+    fn wrong_password_fails_authentication() {
+        let blob = encrypt_mnemonic(&mnemonics(), "correct horse").unwrap();
+
+        assert_eq!(
+            Err(Bip39Error::DecryptionFailed),
+            decrypt_mnemonic(&blob, "battery staple")
+        );
+    }
+
+    #[test]
+    fn tampered_header_is_rejected() {
+        let mut blob = encrypt_mnemonic(&mnemonics(), "correct horse").unwrap();
+
+        blob[4] = 2;
+        assert!(matches!(
+            decrypt_mnemonic(&blob, "correct horse"),
+            Err(Bip39Error::InvalidBackup(_))
+        ));
+    }
+}
@@ -0,0 +1,325 @@
+//! Receive addresses of derived keys, legacy (BIP44), nested SegWit (BIP49),
+//! native SegWit (BIP84) and, behind `taproot` feature, Taproot (BIP86)
+//! # Resources
+//! https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki
+//! https://github.com/bitcoin/bips/blob/master/bip-0049.mediawiki
+//! https://github.com/bitcoin/bips/blob/master/bip-0084.mediawiki
+//! https://github.com/satoshilabs/slips/blob/master/slip-0132.md
+use super::{hash160, Bip32Error, DerivationPath, ExtendedPrivKey, Network, HARDENED};
+use bech32::{u5, ToBase32, Variant};
+use std::str::FromStr;
+
+/// Script type of the addresses, each one has its own purpose, the first index of the path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AddressType {
+    /// P2PKH, "1...", m/44'/...
+    Legacy,
+    /// P2WPKH nested in P2SH, "3...", m/49'/...
+    P2shSegwit,
+    /// P2WPKH, "bc1q...", m/84'/...
+    NativeSegwit,
+    /// P2TR key path, "bc1p...", m/86'/...
+    #[cfg(feature = "taproot")]
+    Taproot,
+}
+
+impl AddressType {
+    /// Purpose of the account path, without the hardened bit
+    pub fn purpose(&self) -> u32 {
+        match self {
+            AddressType::Legacy => 44,
+            AddressType::P2shSegwit => 49,
+            AddressType::NativeSegwit => 84,
+            #[cfg(feature = "taproot")]
+            AddressType::Taproot => 86,
+        }
+    }
+
+    /// Account path, m/purpose'/coin_type'/account', receive addresses are under its /0
+    pub fn account_path(
+        &self,
+        account: u32,
+        network: Network,
+    ) -> Result<DerivationPath, Bip32Error> {
+        if account >= HARDENED {
+            return Err(Bip32Error::PathIndexTooLarge(account.to_string()));
+        }
+
+        Ok(DerivationPath::from(vec![
+            self.purpose() | HARDENED,
+            network.coin_type() | HARDENED,
+            account | HARDENED,
+        ]))
+    }
+
+    /// Address type of the keys under the path, inferred from its purpose.
+    /// Other purposes, e.g. plain BIP32 paths, are `InvalidAddressType`, so an unknown
+    /// script type is not mistaken for a legacy one.
+    pub fn from_path(path: &DerivationPath) -> Result<AddressType, Bip32Error> {
+        match path.indices().first() {
+            Some(&index) if index == 44 | HARDENED => Ok(AddressType::Legacy),
+            Some(&index) if index == 49 | HARDENED => Ok(AddressType::P2shSegwit),
+            Some(&index) if index == 84 | HARDENED => Ok(AddressType::NativeSegwit),
+            #[cfg(feature = "taproot")]
+            Some(&index) if index == 86 | HARDENED => Ok(AddressType::Taproot),
+            _ => Err(Bip32Error::InvalidAddressType(path.to_string())),
+        }
+    }
+
+    /// SLIP-132 version bytes of the extended private key, "xprv", "yprv", "zprv" on mainnet.
+    /// Taproot has no SLIP-132 version, its keys are "xprv".
+    pub fn private_version(&self, network: Network) -> [u8; 4] {
+        match (self, network) {
+            (AddressType::Legacy, network) => network.private_version(),
+            #[cfg(feature = "taproot")]
+            (AddressType::Taproot, network) => network.private_version(),
+            (AddressType::P2shSegwit, Network::Bitcoin) => [0x04, 0x9d, 0x78, 0x78],
+            (AddressType::P2shSegwit, Network::Testnet) => [0x04, 0x4a, 0x4e, 0x28],
+            (AddressType::NativeSegwit, Network::Bitcoin) => [0x04, 0xb2, 0x43, 0x0c],
+            (AddressType::NativeSegwit, Network::Testnet) => [0x04, 0x5f, 0x18, 0xbc],
+        }
+    }
+
+    /// SLIP-132 version bytes of the extended public key, "xpub", "ypub", "zpub" on mainnet.
+    /// Taproot has no SLIP-132 version, its keys are "xpub".
+    pub fn public_version(&self, network: Network) -> [u8; 4] {
+        match (self, network) {
+            (AddressType::Legacy, network) => network.public_version(),
+            #[cfg(feature = "taproot")]
+            (AddressType::Taproot, network) => network.public_version(),
+            (AddressType::P2shSegwit, Network::Bitcoin) => [0x04, 0x9d, 0x7c, 0xb2],
+            (AddressType::P2shSegwit, Network::Testnet) => [0x04, 0x4a, 0x52, 0x62],
+            (AddressType::NativeSegwit, Network::Bitcoin) => [0x04, 0xb2, 0x47, 0x46],
+            (AddressType::NativeSegwit, Network::Testnet) => [0x04, 0x5f, 0x1c, 0xf6],
+        }
+    }
+}
+
+/// Parses names used by the CLI: "native", "legacy", "p2sh" or, behind `taproot` feature, "taproot"
+impl FromStr for AddressType {
+    type Err = Bip32Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "native" => Ok(AddressType::NativeSegwit),
+            "legacy" => Ok(AddressType::Legacy),
+            "p2sh" => Ok(AddressType::P2shSegwit),
+            #[cfg(feature = "taproot")]
+            "taproot" => Ok(AddressType::Taproot),
+            _ => Err(Bip32Error::InvalidAddressType(s.to_string())),
+        }
+    }
+}
+
+impl ExtendedPrivKey {
+    /// Address of this key, e.g. the first native SegWit receive address for m/84'/0'/0'/0/0.
+    /// Only Taproot can fail, see `p2tr_address`.
+    pub fn address(
+        &self,
+        address_type: AddressType,
+        network: Network,
+    ) -> Result<String, Bip32Error> {
+        let pubkey_hash = hash160(&self.public_key());
+
+        match address_type {
+            AddressType::Legacy => Ok(base58_address(network.p2pkh_version(), &pubkey_hash)),
+            AddressType::P2shSegwit => {
+                // Redeem script is the witness program of native SegWit: OP_0 <20 bytes>
+                let mut redeem_script = vec![0x00, 0x14];
+                redeem_script.extend_from_slice(&pubkey_hash);
+
+                Ok(base58_address(
+                    network.p2sh_version(),
+                    &hash160(&redeem_script),
+                ))
+            }
+            AddressType::NativeSegwit => Ok(segwit_address(network, 0, &pubkey_hash)),
+            #[cfg(feature = "taproot")]
+            AddressType::Taproot => self.p2tr_address(network),
+        }
+    }
+
+    /// Extended private key with SLIP-132 version of the address type, e.g. "zprv"
+    pub fn to_slip132_prv(&self, address_type: AddressType, network: Network) -> String {
+        self.serialize_private(address_type.private_version(network))
+    }
+
+    /// Extended public key with SLIP-132 version of the address type, e.g. "zpub"
+    pub fn to_slip132_pub(&self, address_type: AddressType, network: Network) -> String {
+        self.serialize_public(address_type.public_version(network))
+    }
+}
+
+pub(super) fn base58_address(version: u8, hash: &[u8; 20]) -> String {
+    let mut data = Vec::with_capacity(21);
+    data.push(version);
+    data.extend_from_slice(hash);
+
+    bs58::encode(data).with_check().into_string()
+}
+
+/// Bech32 for witness version 0, bech32m for the later ones, BIP350
+pub(super) fn segwit_address(network: Network, version: u8, program: &[u8]) -> String {
+    let mut data = vec![u5::try_from_u8(version).expect("Witness version above 16")];
+    data.extend(program.to_base32());
+    let variant = if version == 0 {
+        Variant::Bech32
+    } else {
+        Variant::Bech32m
+    };
+
+    bech32::encode(network.hrp(), data, variant).expect("Invalid human readable part")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::{abandon_about, Passphrase};
+
+    fn derive(path: &str) -> ExtendedPrivKey {
+        ExtendedPrivKey::from_seed(&abandon_about().to_seed(&Passphrase::new("")))
+            .unwrap()
+            .derive(&path.parse().unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn bip44_test_vector() {
+        assert_eq!(
+            "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA",
+            derive("m/44'/0'/0'/0/0")
+                .address(AddressType::Legacy, Network::Bitcoin)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn bip49_test_vector() {
+        assert_eq!(
+            "37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf",
+            derive("m/49'/0'/0'/0/0")
+                .address(AddressType::P2shSegwit, Network::Bitcoin)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn bip84_test_vectors() {
+        let account = derive("m/84'/0'/0'");
+
+        assert_eq!(
+            "zprvAdG4iTXWBoARxkkzNpNh8r6Qag3irQB8PzEMkAFeTRXxHpbF9z4QgEvBRmfvqWvGp42t42nvgGpNgYSJA9iefm1yYNZKEm7z6qUWCroSQnE",
+            account.to_slip132_prv(AddressType::NativeSegwit, Network::Bitcoin)
+        );
+        assert_eq!(
+            "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs",
+            account.to_slip132_pub(AddressType::NativeSegwit, Network::Bitcoin)
+        );
+
+        for (path, address) in &[
+            (
+                "m/84'/0'/0'/0/0",
+                "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu",
+            ),
+            (
+                "m/84'/0'/0'/0/1",
+                "bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g",
+            ),
+            (
+                "m/84'/0'/0'/1/0",
+                "bc1q8c6fshw2dlwun7ekn9qwf37cu2rn755upcp6el",
+            ),
+        ] {
+            assert_eq!(
+                *address,
+                derive(path)
+                    .address(AddressType::NativeSegwit, Network::Bitcoin)
+                    .unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn address_type_is_inferred_from_purpose() {
+        for (path, address_type) in &[
+            ("m/44'/0'/0'/0/0", AddressType::Legacy),
+            ("m/49'/0'/0'/0/0", AddressType::P2shSegwit),
+            ("m/84'/0'/0'/0/0", AddressType::NativeSegwit),
+        ] {
+            assert_eq!(
+                Ok(*address_type),
+                AddressType::from_path(&path.parse().unwrap())
+            );
+        }
+
+        #[cfg(feature = "taproot")]
+        assert_eq!(
+            Ok(AddressType::Taproot),
+            AddressType::from_path(&"m/86'/0'/0'/0/0".parse().unwrap())
+        );
+        #[cfg(not(feature = "taproot"))]
+        assert_eq!(
+            Err(Bip32Error::InvalidAddressType(
+                "m/86'/0'/0'/0/0".to_string()
+            )),
+            AddressType::from_path(&"m/86'/0'/0'/0/0".parse().unwrap())
+        );
+
+        for path in &["m/84/0'/0'/0/0", "m/0/0", "m"] {
+            assert_eq!(
+                Err(Bip32Error::InvalidAddressType(path.to_string())),
+                AddressType::from_path(&path.parse().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn account_paths_follow_purpose() {
+        assert_eq!(
+            "m/84'/0'/0'",
+            AddressType::NativeSegwit
+                .account_path(0, Network::Bitcoin)
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            "m/44'/1'/5'",
+            AddressType::Legacy
+                .account_path(5, Network::Testnet)
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            Err(Bip32Error::PathIndexTooLarge("2147483648".to_string())),
+            AddressType::P2shSegwit.account_path(HARDENED, Network::Bitcoin)
+        );
+    }
+
+    #[test]
+    fn address_type_is_parsed_from_cli_names() {
+        assert_eq!(Ok(AddressType::NativeSegwit), "native".parse());
+        assert_eq!(Ok(AddressType::Legacy), "legacy".parse());
+        assert_eq!(Ok(AddressType::P2shSegwit), "p2sh".parse());
+        assert_eq!(
+            Err(Bip32Error::InvalidAddressType("segwit".to_string())),
+            "segwit".parse::<AddressType>()
+        );
+    }
+
+    #[test]
+    fn testnet_addresses_have_testnet_prefixes() {
+        let key = derive("m/84'/1'/0'/0/0");
+
+        assert!(key
+            .address(AddressType::NativeSegwit, Network::Testnet)
+            .unwrap()
+            .starts_with("tb1q"));
+        assert!(key
+            .address(AddressType::P2shSegwit, Network::Testnet)
+            .unwrap()
+            .starts_with('2'));
+        assert!(key
+            .to_slip132_pub(AddressType::NativeSegwit, Network::Testnet)
+            .starts_with("vpub"));
+    }
+}
@@ -0,0 +1,158 @@
+//! BIP85, deterministic entropy for independent child wallets derived from the master key,
+//! so a single backup covers all of them
+//! # Resources
+//! https://github.com/bitcoin/bips/blob/master/bip-0085.mediawiki
+use super::{Bip32Error, DerivationPath, ExtendedPrivKey, HARDENED};
+use crate::bip39::{
+    generate_mnemonics_from_entropy_in, Bip39Error, Language, Mnemonic, WordsCount,
+};
+use crate::error::WalletError;
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha512;
+use zeroize::Zeroizing;
+
+/// First index of all BIP85 paths
+const PURPOSE: u32 = 83_696_968;
+
+/// Application number of BIP39 child mnemonics
+const BIP39_APP: u32 = 39;
+
+impl ExtendedPrivKey {
+    /// BIP85 child mnemonic at m/83696968'/39'/language'/words'/index', has to be called
+    /// on the master key. Language number is `Language::id`, Portuguese is not in BIP85
+    /// and gives `LanguageNotInBip85`.
+    pub fn bip85_mnemonic(
+        &self,
+        language: Language,
+        words: WordsCount,
+        index: u32,
+    ) -> Result<Mnemonic, WalletError> {
+        if language == Language::Portuguese {
+            return Err(Bip32Error::LanguageNotInBip85(language).into());
+        }
+
+        let entropy = Zeroizing::new(bip85_entropy(
+            self,
+            BIP39_APP,
+            &[language.id().into(), words as u32, index],
+        )?);
+
+        Ok(generate_mnemonics_from_entropy_in(
+            &entropy[..words.keyspace_bits() / 8],
+            language,
+        )?)
+    }
+}
+
+/// Raw BIP85 application entropy, HMAC-SHA512 of the private key at
+/// m/83696968'/app'/path_tail', all indices are hardened. Whole 64 bytes are returned,
+/// applications truncate them, e.g. BIP39 takes 16 - 32 bytes, WIF and XPRV take 32.
+pub fn bip85_entropy(
+    master: &ExtendedPrivKey,
+    app: u32,
+    path_tail: &[u32],
+) -> Result<Vec<u8>, WalletError> {
+    let mut indices = vec![PURPOSE, app];
+    indices.extend_from_slice(path_tail);
+
+    if let Some(index) = indices.iter().find(|&&index| index >= HARDENED) {
+        return Err(Bip32Error::PathIndexTooLarge(index.to_string()).into());
+    }
+
+    let path = DerivationPath::from(
+        indices
+            .iter()
+            .map(|index| index | HARDENED)
+            .collect::<Vec<_>>(),
+    );
+    let key = master.derive(&path)?;
+
+    let mut mac = Hmac::<Sha512>::new_from_slice(b"bip-entropy-from-k")
+        .map_err(|e| Bip39Error::Internal(e.to_string()))?;
+    mac.update(&*key.private_key());
+
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Master key of BIP85 test vectors
+    fn master() -> ExtendedPrivKey {
+        "xprv9s21ZrQH143K2LBWUUQRFXhucrQqBpKdRRxNVq2zBqsx8HVqFk2uYo8kmbaLLHRdqtQpUm98uKfu3vca1LqdGhUtyoFnCNkfmXRyPXLjbKb"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn bip85_entropy_test_vectors() {
+        assert_eq!(
+            "efecfbccffea313214232d29e71563d941229afb4338c21f9517c41aaa0d16f00b83d2a09ef747e7a64e8e2bd5a14869e693da66ce94ac2da570ab7ee48618f7",
+            hex::encode(bip85_entropy(&master(), 0, &[0]).unwrap())
+        );
+        assert_eq!(
+            "70c6e3e8ebee8dc4c0dbba66076819bb8c09672527c4277ca8729532ad711872218f826919f6b67218adde99018a6df9095ab2b58d803b5b93ec9802085a690e",
+            hex::encode(bip85_entropy(&master(), 0, &[1]).unwrap())
+        );
+    }
+
+    #[test]
+    fn bip85_bip39_test_vectors() {
+        for (words, phrase) in &[
+            (
+                WordsCount::_12,
+                "girl mad pet galaxy egg matter matrix prison refuse sense ordinary nose",
+            ),
+            (
+                WordsCount::_18,
+                "near account window bike charge season chef number sketch tomorrow excuse sniff circle vital hockey outdoor supply token",
+            ),
+            (
+                WordsCount::_24,
+                "puppy ocean match cereal symbol another shed magic wrap hammer bulb intact gadget divorce twin tonight reason outdoor destroy simple truth cigar social volcano",
+            ),
+        ] {
+            assert_eq!(
+                *phrase,
+                master()
+                    .bip85_mnemonic(Language::English, *words, 0)
+                    .unwrap()
+                    .to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn children_are_independent() {
+        let master = master();
+        let first = master
+            .bip85_mnemonic(Language::English, WordsCount::_12, 0)
+            .unwrap();
+        let second = master
+            .bip85_mnemonic(Language::English, WordsCount::_12, 1)
+            .unwrap();
+
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn portuguese_is_rejected() {
+        assert_eq!(
+            Err(WalletError::Bip32(Bip32Error::LanguageNotInBip85(
+                Language::Portuguese
+            ))),
+            master().bip85_mnemonic(Language::Portuguese, WordsCount::_12, 0)
+        );
+    }
+
+    #[test]
+    fn too_large_index_is_rejected() {
+        assert_eq!(
+            Err(WalletError::Bip32(Bip32Error::PathIndexTooLarge(
+                HARDENED.to_string()
+            ))),
+            master().bip85_mnemonic(Language::English, WordsCount::_12, HARDENED)
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! First receive addresses of other coins derived from the same seed, as multi-coin
+//! wallets show them on onboarding
+//! # Resources
+//! https://github.com/satoshilabs/slips/blob/master/slip-0044.md
+//! https://github.com/ethereum/EIPs/blob/master/EIPS/eip-55.md
+use super::{
+    base58_address, hash160, AddressType, Bip32Error, DerivationPath, ExtendedPrivKey, Network,
+};
+use super::{Wallet, HARDENED};
+use crate::bip39::Mnemonic;
+use crate::error::WalletError;
+use sha3::{Digest, Keccak256};
+use std::collections::BTreeMap;
+
+/// Litecoin P2PKH version byte, "L..." addresses
+const LITECOIN_P2PKH_VERSION: u8 = 0x30;
+
+/// Coins with their standard paths and address formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Coin {
+    /// Native SegWit, "bc1q...", m/84'/0'/0'/0/0
+    Bitcoin,
+    /// EIP-55 checksummed, "0x...", m/44'/60'/0'/0/0
+    Ethereum,
+    /// P2PKH, "L...", m/44'/2'/0'/0/0
+    Litecoin,
+}
+
+impl Coin {
+    /// SLIP-44 coin type
+    pub fn coin_type(&self) -> u32 {
+        match self {
+            Coin::Bitcoin => 0,
+            Coin::Ethereum => 60,
+            Coin::Litecoin => 2,
+        }
+    }
+
+    /// Path of the first receive address, m/purpose'/coin_type'/0'/0/0
+    pub fn first_address_path(&self) -> DerivationPath {
+        let purpose = match self {
+            Coin::Bitcoin => AddressType::NativeSegwit.purpose(),
+            Coin::Ethereum | Coin::Litecoin => AddressType::Legacy.purpose(),
+        };
+
+        DerivationPath::from(vec![
+            purpose | HARDENED,
+            self.coin_type() | HARDENED,
+            HARDENED,
+            0,
+            0,
+        ])
+    }
+
+    /// Address of the key in format of the coin
+    pub fn address(&self, key: &ExtendedPrivKey) -> Result<String, Bip32Error> {
+        match self {
+            Coin::Bitcoin => key.address(AddressType::NativeSegwit, Network::Bitcoin),
+            Coin::Ethereum => Ok(key.eth_address()),
+            Coin::Litecoin => Ok(base58_address(
+                LITECOIN_P2PKH_VERSION,
+                &hash160(&key.public_key()),
+            )),
+        }
+    }
+}
+
+impl ExtendedPrivKey {
+    /// Ethereum address of this key with EIP-55 mixed case checksum, e.g. "0x9858Ef..."
+    pub fn eth_address(&self) -> String {
+        let address = hex::encode(self.eth_address_bytes());
+        let hash = Keccak256::digest(address.as_bytes());
+
+        // Letter is uppercase when its nibble of the hash of lowercase hex is 8 or above
+        let checksummed: String = address
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let nibble = (hash[i / 2] >> (4 * (1 - i % 2))) & 0x0f;
+                if nibble >= 8 {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect();
+
+        format!("0x{}", checksummed)
+    }
+
+    /// Last 20 bytes of Keccak-256 of the uncompressed public key, without its 0x04 prefix
+    pub(super) fn eth_address_bytes(&self) -> [u8; 20] {
+        let mut address = [0; 20];
+        address.copy_from_slice(&Keccak256::digest(&self.public_key_uncompressed()[1..])[12..]);
+
+        address
+    }
+}
+
+/// First receive address of every coin, seed is derived once and shared by all of them
+pub fn multi_coin_addresses(
+    m: &Mnemonic,
+    passphrase: &str,
+    coins: &[Coin],
+) -> Result<BTreeMap<Coin, String>, WalletError> {
+    let wallet = Wallet::new(m, passphrase)?;
+
+    coins
+        .iter()
+        .map(|coin| {
+            let key = wallet.derive(&coin.first_address_path())?;
+
+            Ok((*coin, coin.address(&key)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::abandon_about;
+
+    #[test]
+    fn first_addresses_of_test_mnemonic() {
+        let addresses =
+            multi_coin_addresses(&abandon_about(), "", &[Coin::Ethereum, Coin::Bitcoin]).unwrap();
+
+        assert_eq!(2, addresses.len());
+        assert_eq!(
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu",
+            addresses[&Coin::Bitcoin]
+        );
+        assert_eq!(
+            "0x9858EfFD232B4033E47d90003D41EC34EcaEda94",
+            addresses[&Coin::Ethereum]
+        );
+    }
+
+    #[test]
+    fn litecoin_address_of_test_mnemonic() {
+        let addresses = multi_coin_addresses(&abandon_about(), "", &[Coin::Litecoin]).unwrap();
+
+        assert_eq!(
+            "LUWPbpM43E2p7ZSh8cyTBEkvpHmr3cB8Ez",
+            addresses[&Coin::Litecoin]
+        );
+    }
+
+    #[test]
+    fn coin_paths_follow_slip44() {
+        for (coin, path) in &[
+            (Coin::Bitcoin, "m/84'/0'/0'/0/0"),
+            (Coin::Ethereum, "m/44'/60'/0'/0/0"),
+            (Coin::Litecoin, "m/44'/2'/0'/0/0"),
+        ] {
+            assert_eq!(*path, coin.first_address_path().to_string());
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Wallet policy of an application, so words count, language, network and address type
+//! are not passed around everywhere
+use super::{AddressType, Network, Wallet};
+use crate::bip39::{generate_mnemonics_in, Language, Mnemonic, WordsCount};
+use crate::entropy::EntropySource;
+use crate::error::WalletError;
+
+/// Defaults of the wallets an application creates and restores,
+/// `Default` is 24 English words, native SegWit addresses on mainnet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WalletConfig {
+    pub words: WordsCount,
+    pub language: Language,
+    pub network: Network,
+    pub address_type: AddressType,
+}
+
+impl Default for WalletConfig {
+    fn default() -> Self {
+        WalletConfig {
+            words: WordsCount::_24,
+            language: Language::English,
+            network: Network::Bitcoin,
+            address_type: AddressType::NativeSegwit,
+        }
+    }
+}
+
+impl WalletConfig {
+    /// New mnemonic with configured words count and language
+    pub fn generate(&self, ent: &dyn EntropySource) -> Result<Mnemonic, WalletError> {
+        Ok(generate_mnemonics_in(self.words, ent, self.language)?)
+    }
+
+    /// Wallet of the phrase in configured language, passphrase is "" if there is none
+    pub fn restore(&self, phrase: &str, passphrase: &str) -> Result<Wallet, WalletError> {
+        let mnemonic = Mnemonic::from_phrase_in(phrase, self.language)?;
+
+        Wallet::new(&mnemonic, passphrase)
+    }
+
+    /// Receive address of the first account with configured address type and network
+    pub fn receive_address(&self, wallet: &Wallet, index: u32) -> Result<String, WalletError> {
+        wallet.receive_address(self.address_type, self.network, index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::{abandon_about, Bip39Error};
+    use crate::entropy::BasicEntropy;
+
+    #[test]
+    fn default_config_gives_24_words_and_mainnet_addresses() {
+        let config = WalletConfig::default();
+
+        let mnemonic = config.generate(&BasicEntropy).unwrap();
+        assert_eq!(24, mnemonic.len());
+        assert_eq!(Language::English, mnemonic.language());
+
+        let wallet = config.restore(&mnemonic.to_string(), "").unwrap();
+        for index in 0..3 {
+            assert!(config
+                .receive_address(&wallet, index)
+                .unwrap()
+                .starts_with("bc1q"));
+        }
+    }
+
+    #[test]
+    fn restore_follows_config() {
+        let config = WalletConfig {
+            network: Network::Testnet,
+            address_type: AddressType::Legacy,
+            ..WalletConfig::default()
+        };
+        let wallet = config.restore(&abandon_about().to_string(), "").unwrap();
+        let address = config.receive_address(&wallet, 0).unwrap();
+        assert!(address.starts_with('m') || address.starts_with('n'));
+
+        assert_eq!(
+            Some(WalletError::Bip39(Bip39Error::InvalidChecksum)),
+            config.restore(&"abandon ".repeat(12), "").err()
+        );
+    }
+}
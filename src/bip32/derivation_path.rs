@@ -0,0 +1,186 @@
+//! BIP32 derivation paths, like m/84'/0'/0'/0/0
+//! # Resources
+//! https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki
+use crate::bip39::{Bip39Error, Language};
+use serde::Serialize;
+use std::fmt;
+use std::num::IntErrorKind;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Indices starting from this one are hardened
+pub const HARDENED: u32 = 0x8000_0000;
+
+#[derive(Error, Debug, PartialEq, Serialize)]
+#[serde(tag = "error", content = "details")]
+pub enum Bip32Error {
+    #[error("Invalid derivation path {}", .0)]
+    InvalidPath(String),
+
+    #[error("Derivation path index {} too large, has to be below 2^31", .0)]
+    PathIndexTooLarge(String),
+
+    #[error("Invalid master key, use different seed")]
+    InvalidMasterKey,
+
+    #[error("Invalid child key at index {}, use the next one", .0)]
+    InvalidChildKey(u32),
+
+    #[error("Derivation path deeper than 255 levels")]
+    TooDeep,
+
+    #[error("Invalid extended private key")]
+    InvalidExtendedKey,
+
+    #[error("Invalid address type {}, expected native, legacy, p2sh or their path purpose", .0)]
+    InvalidAddressType(String),
+
+    #[error("Invalid Taproot tweak, use the next key")]
+    InvalidTweak,
+
+    #[error("Language {:?} has no BIP85 number", .0)]
+    LanguageNotInBip85(Language),
+
+    #[error("Mnemonic of wallet {}, expected {}", .found, .expected)]
+    FingerprintMismatch { expected: String, found: String },
+
+    #[error(transparent)]
+    Bip39(#[from] Bip39Error),
+}
+
+/// Path from the master key to the derived one, as a list of child indices
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivationPath {
+    indices: Vec<u32>,
+}
+
+impl DerivationPath {
+    /// Child indices, hardened ones have HARDENED bit set
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// Path of the direct child, e.g. m/84'/0'/0'/0 for m/84'/0'/0' and 0
+    pub fn child(&self, index: u32) -> DerivationPath {
+        let mut indices = self.indices.clone();
+        indices.push(index);
+
+        DerivationPath { indices }
+    }
+}
+
+/// Path from raw indices, hardened ones have HARDENED bit set, so all u32 are valid
+impl From<Vec<u32>> for DerivationPath {
+    fn from(indices: Vec<u32>) -> Self {
+        DerivationPath { indices }
+    }
+}
+
+/// Parses paths like "m/84'/0'/0'/0/0", hardening can be marked with ', h or H.
+/// Index itself has to be below 2^31, the high bit is set only by the marker.
+impl FromStr for DerivationPath {
+    type Err = Bip32Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('/');
+
+        if parts.next() != Some("m") {
+            return Err(Bip32Error::InvalidPath(s.to_string()));
+        }
+
+        let mut indices = vec![];
+
+        for part in parts {
+            let (number, hardened) = match part.strip_suffix(&['\'', 'h', 'H'][..]) {
+                Some(number) => (number, true),
+                None => (part, false),
+            };
+
+            let index = match number.parse::<u32>() {
+                Ok(index) => index,
+                Err(e) if *e.kind() == IntErrorKind::PosOverflow => {
+                    return Err(Bip32Error::PathIndexTooLarge(part.to_string()))
+                }
+                Err(_) => return Err(Bip32Error::InvalidPath(s.to_string())),
+            };
+
+            // Otherwise unhardened index would silently become a hardened one,
+            // and hardened one would overflow
+            if index >= HARDENED {
+                return Err(Bip32Error::PathIndexTooLarge(part.to_string()));
+            }
+
+            if hardened {
+                indices.push(index | HARDENED);
+            } else {
+                indices.push(index);
+            }
+        }
+
+        Ok(DerivationPath { indices })
+    }
+}
+
+/// Displays path in canonical form, hardened indices are marked with '
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+
+        for index in &self.indices {
+            if index & HARDENED != 0 {
+                write!(f, "/{}'", index & !HARDENED)?;
+            } else {
+                write!(f, "/{}", index)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hardening_markers_are_equivalent() {
+        let apostrophe: DerivationPath = "m/84'/0'".parse().unwrap();
+        let mixed: DerivationPath = "m/84h/0'".parse().unwrap();
+        let upper: DerivationPath = "m/84H/0H".parse().unwrap();
+
+        assert_eq!(&[84 | HARDENED, HARDENED], apostrophe.indices());
+        assert_eq!(apostrophe, mixed);
+        assert_eq!(apostrophe, upper);
+    }
+
+    #[test]
+    fn display_is_canonical() {
+        let path: DerivationPath = "m/84h/0H/0'/0/1".parse().unwrap();
+
+        assert_eq!("m/84'/0'/0'/0/1", path.to_string());
+        assert_eq!("m", "m".parse::<DerivationPath>().unwrap().to_string());
+    }
+
+    #[test]
+    fn invalid_paths_are_rejected() {
+        for path in &["", "84'/0'", "m/", "m/x", "m/0''", "m/-1"] {
+            assert_eq!(
+                Err(Bip32Error::InvalidPath(path.to_string())),
+                path.parse::<DerivationPath>()
+            );
+        }
+    }
+
+    #[test]
+    fn too_large_indices_are_rejected() {
+        for index in &["2147483648", "2147483648'", "4294967295", "4294967296h"] {
+            assert_eq!(
+                Err(Bip32Error::PathIndexTooLarge(index.to_string())),
+                format!("m/0/{}", index).parse::<DerivationPath>()
+            );
+        }
+
+        let path: DerivationPath = "m/2147483647'/2147483647".parse().unwrap();
+        assert_eq!(&[0xFFFF_FFFF, 0x7FFF_FFFF], path.indices());
+    }
+}
@@ -0,0 +1,133 @@
+//! Exports comparable with the reference tool, iancoleman.io BIP39
+//! # Resources
+//! https://iancoleman.io/bip39/
+use super::{AddressType, Bip32Error, DerivationPath, Network, Wallet, HARDENED};
+use crate::bip39::{Bip39Error, Mnemonic};
+use crate::error::WalletError;
+use serde::Serialize;
+
+/// Fields of the reference tool, in the same order
+#[derive(Serialize)]
+struct IancolemanExport {
+    bip32_root_key: String,
+    account_extended_private_key: String,
+    account_extended_public_key: String,
+    derived_addresses: Vec<DerivedAddress>,
+}
+
+/// Row of the "Derived Addresses" table
+#[derive(Serialize)]
+struct DerivedAddress {
+    path: String,
+    address: String,
+    public_key: String,
+    private_key: String,
+}
+
+/// JSON with "BIP32 Root Key", account extended keys and the first `count` rows of
+/// "Derived Addresses" of the reference tool, for "BIP32 Derivation Path" like m/84'/0'/0'/0.
+/// Account is the parent of the path, its keys have SLIP-132 versions of the address type,
+/// e.g. zprv and zpub for BIP84. Address type is inferred from the purpose of the path.
+pub fn iancoleman_export(
+    m: &Mnemonic,
+    passphrase: &str,
+    path: &DerivationPath,
+    count: u32,
+    network: Network,
+) -> Result<String, WalletError> {
+    // Otherwise the last rows would be hardened children
+    if count > HARDENED {
+        return Err(Bip32Error::PathIndexTooLarge(count.to_string()).into());
+    }
+
+    let wallet = Wallet::new(m, passphrase)?;
+    let address_type = AddressType::from_path(path)?;
+
+    let indices = path.indices();
+    let account_path = DerivationPath::from(indices[..indices.len().saturating_sub(1)].to_vec());
+    let account = wallet.derive(&account_path)?;
+    let parent = wallet.derive(path)?;
+
+    let derived_addresses = (0..count)
+        .map(|index| {
+            let key = parent.child(index)?;
+
+            Ok(DerivedAddress {
+                path: path.child(index).to_string(),
+                address: key.address(address_type, network)?,
+                public_key: hex::encode(key.public_key()),
+                private_key: key.to_wif(network),
+            })
+        })
+        .collect::<Result<_, WalletError>>()?;
+
+    let export = IancolemanExport {
+        bip32_root_key: wallet.master().to_xprv(network),
+        account_extended_private_key: account.to_slip132_prv(address_type, network),
+        account_extended_public_key: account.to_slip132_pub(address_type, network),
+        derived_addresses,
+    };
+
+    Ok(serde_json::to_string_pretty(&export).map_err(|e| Bip39Error::Internal(e.to_string()))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::abandon_about;
+    use serde_json::Value;
+
+    fn export(path: &str, count: u32) -> Value {
+        let json = iancoleman_export(
+            &abandon_about(),
+            "",
+            &path.parse().unwrap(),
+            count,
+            Network::Bitcoin,
+        )
+        .unwrap();
+
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn bip44_export_matches_reference_tool() {
+        let export = export("m/44'/0'/0'/0", 3);
+
+        assert_eq!(
+            "xprv9s21ZrQH143K3GJpoapnV8SFfukcVBSfeCficPSGfubmSFDxo1kuHnLisriDvSnRRuL2Qrg5ggqHKNVpxR86QEC8w35uxmGoggxtQTPvfUu",
+            export["bip32_root_key"]
+        );
+        assert_eq!(
+            "xpub6BosfCnifzxcFwrSzQiqu2DBVTshkCXacvNsWGYJVVhhawA7d4R5WSWGFNbi8Aw6ZRc1brxMyWMzG3DSSSSoekkudhUd9yLb6qx39T9nMdj",
+            export["account_extended_public_key"]
+        );
+
+        let rows = export["derived_addresses"].as_array().unwrap();
+        assert_eq!(3, rows.len());
+        assert_eq!("m/44'/0'/0'/0/0", rows[0]["path"]);
+        assert_eq!("1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA", rows[0]["address"]);
+        assert_eq!("m/44'/0'/0'/0/2", rows[2]["path"]);
+    }
+
+    #[test]
+    fn bip84_export_matches_reference_tool() {
+        let export = export("m/84'/0'/0'/0", 1);
+
+        assert_eq!(
+            "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs",
+            export["account_extended_public_key"]
+        );
+
+        let row = &export["derived_addresses"][0];
+        assert_eq!("bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu", row["address"]);
+        assert_eq!(
+            "0330d54fd0dd420a6e5f8d3624f5f3482cae350f79d5f0753bf5beef9c2d91af3c",
+            row["public_key"]
+        );
+        assert_eq!(
+            "KyZpNDKnfs94vbrwhJneDi77V6jF64PWPF8x5cdJb8ifgg2DUc9d",
+            row["private_key"]
+        );
+    }
+}
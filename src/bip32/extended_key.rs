@@ -0,0 +1,360 @@
+//! BIP32 extended private key and child key derivation
+//! # Resources
+//! https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki
+use super::{Bip32Error, DerivationPath, Network, HARDENED};
+use crate::bip39::Bip39Error;
+use hmac::{Hmac, Mac, NewMac};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::elliptic_curve::PrimeField;
+use k256::{FieldBytes, NonZeroScalar, Scalar, SecretKey};
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256, Sha512};
+use std::fmt;
+use std::str::FromStr;
+use zeroize::Zeroizing;
+
+/// RIPEMD160 of SHA256, identifies keys and is the payload of most addresses
+pub fn hash160(data: &[u8]) -> [u8; 20] {
+    let mut hash = [0; 20];
+    hash.copy_from_slice(&Ripemd160::digest(&Sha256::digest(data)));
+
+    hash
+}
+
+/// Private key with chain code, and its position in the tree, everything needed to derive
+/// children and to serialize it as xprv. Secrets are zeroized on drop.
+#[derive(Clone)]
+pub struct ExtendedPrivKey {
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: Zeroizing<[u8; 32]>,
+    secret_key: SecretKey,
+}
+
+impl ExtendedPrivKey {
+    /// Master key, HMAC-SHA512 of the seed keyed with "Bitcoin seed"
+    pub fn from_seed(seed: &[u8]) -> Result<Self, Bip32Error> {
+        let i = hmac_sha512(b"Bitcoin seed", &[seed])?;
+        let (key, chain_code) = i.split_at(32);
+
+        // Zero or not below the curve order, probability is lower than 1 in 2^127
+        let secret_key = SecretKey::from_slice(key).map_err(|_| Bip32Error::InvalidMasterKey)?;
+
+        Ok(ExtendedPrivKey {
+            depth: 0,
+            parent_fingerprint: [0; 4],
+            child_number: 0,
+            chain_code: to_chain_code(chain_code),
+            secret_key,
+        })
+    }
+
+    /// Derives direct child, hardened one if index has `HARDENED` bit set
+    pub fn child(&self, index: u32) -> Result<Self, Bip32Error> {
+        let depth = self.depth.checked_add(1).ok_or(Bip32Error::TooDeep)?;
+
+        let i = if index & HARDENED != 0 {
+            hmac_sha512(
+                &*self.chain_code,
+                &[&[0], &self.secret_key.to_bytes(), &index.to_be_bytes()],
+            )?
+        } else {
+            hmac_sha512(
+                &*self.chain_code,
+                &[&self.public_key(), &index.to_be_bytes()],
+            )?
+        };
+        let (tweak, chain_code) = i.split_at(32);
+
+        // Tweak not below the curve order, or child key equal to zero,
+        // probability is lower than 1 in 2^127, BIP32 says to use the next index then
+        let tweak: Option<Scalar> = Scalar::from_repr(FieldBytes::clone_from_slice(tweak)).into();
+        let tweak = tweak.ok_or(Bip32Error::InvalidChildKey(index))?;
+        let key: Option<NonZeroScalar> =
+            NonZeroScalar::new(tweak + self.secret_key.to_nonzero_scalar().as_ref()).into();
+        let key = key.ok_or(Bip32Error::InvalidChildKey(index))?;
+
+        Ok(ExtendedPrivKey {
+            depth,
+            parent_fingerprint: self.fingerprint(),
+            child_number: index,
+            chain_code: to_chain_code(chain_code),
+            secret_key: SecretKey::from(key),
+        })
+    }
+
+    /// Derives key at the path, relative to this key, so for the master key it is the full path
+    pub fn derive(&self, path: &DerivationPath) -> Result<Self, Bip32Error> {
+        path.indices()
+            .iter()
+            .try_fold(self.clone(), |key, &index| key.child(index))
+    }
+
+    /// Raw 32 bytes of the private key
+    pub fn private_key(&self) -> Zeroizing<[u8; 32]> {
+        let mut key = Zeroizing::new([0; 32]);
+        key.copy_from_slice(&self.secret_key.to_bytes());
+
+        key
+    }
+
+    /// Private key in Wallet Import Format, for the compressed public key
+    pub fn to_wif(&self, network: Network) -> String {
+        let mut data = Zeroizing::new(Vec::with_capacity(34));
+        data.push(network.wif_version());
+        data.extend_from_slice(&*self.private_key());
+        data.push(0x01);
+
+        bs58::encode(&*data).with_check().into_string()
+    }
+
+    /// SEC1 compressed public key
+    pub fn public_key(&self) -> [u8; 33] {
+        let mut key = [0; 33];
+        key.copy_from_slice(
+            self.secret_key
+                .public_key()
+                .to_encoded_point(true)
+                .as_bytes(),
+        );
+
+        key
+    }
+
+    /// SEC1 uncompressed public key, 0x04 followed by x and y, used by Ethereum
+    pub fn public_key_uncompressed(&self) -> [u8; 65] {
+        let mut key = [0; 65];
+        key.copy_from_slice(
+            self.secret_key
+                .public_key()
+                .to_encoded_point(false)
+                .as_bytes(),
+        );
+
+        key
+    }
+
+    /// Key identifier, hash160 of the public key
+    pub fn identifier(&self) -> [u8; 20] {
+        hash160(&self.public_key())
+    }
+
+    /// First 4 bytes of the identifier. Children refer to their parent with it,
+    /// fingerprint of the master key identifies the whole wallet
+    pub fn fingerprint(&self) -> [u8; 4] {
+        let mut fingerprint = [0; 4];
+        fingerprint.copy_from_slice(&self.identifier()[..4]);
+
+        fingerprint
+    }
+
+    /// Number of derivations from the master key, 0 for the master key itself
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Index this key was derived with, 0 for the master key
+    pub fn child_number(&self) -> u32 {
+        self.child_number
+    }
+
+    /// Base58check encoded xprv (tprv)
+    pub fn to_xprv(&self, network: Network) -> String {
+        self.serialize_private(network.private_version())
+    }
+
+    /// Base58check encoded xpub (tpub) of the corresponding extended public key
+    pub fn to_xpub(&self, network: Network) -> String {
+        self.serialize_public(network.public_version())
+    }
+
+    /// Serializes private key with any version bytes, e.g. SLIP-132 "zprv"
+    pub(super) fn serialize_private(&self, version: [u8; 4]) -> String {
+        let mut key = Zeroizing::new([0; 33]);
+        key[1..].copy_from_slice(&*self.private_key());
+
+        self.serialize(version, &key)
+    }
+
+    /// Serializes public key with any version bytes, e.g. SLIP-132 "zpub"
+    pub(super) fn serialize_public(&self, version: [u8; 4]) -> String {
+        self.serialize(version, &self.public_key())
+    }
+
+    fn serialize(&self, version: [u8; 4], key: &[u8; 33]) -> String {
+        let mut data = Zeroizing::new(Vec::with_capacity(78));
+        data.extend_from_slice(&version);
+        data.push(self.depth);
+        data.extend_from_slice(&self.parent_fingerprint);
+        data.extend_from_slice(&self.child_number.to_be_bytes());
+        data.extend_from_slice(&*self.chain_code);
+        data.extend_from_slice(key);
+
+        bs58::encode(&*data).with_check().into_string()
+    }
+}
+
+/// Parses base58check encoded xprv (tprv). Error never contains the key, it is a secret.
+impl FromStr for ExtendedPrivKey {
+    type Err = Bip32Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let data = Zeroizing::new(
+            bs58::decode(s)
+                .with_check(None)
+                .into_vec()
+                .map_err(|_| Bip32Error::InvalidExtendedKey)?,
+        );
+
+        if data.len() != 78 {
+            return Err(Bip32Error::InvalidExtendedKey);
+        }
+
+        let (version, rest) = data.split_at(4);
+        let (&depth, rest) = rest.split_first().ok_or(Bip32Error::InvalidExtendedKey)?;
+        let (parent_fingerprint, rest) = rest.split_at(4);
+        let (child_number, rest) = rest.split_at(4);
+        let (chain_code, key) = rest.split_at(32);
+
+        let networks = [Network::Bitcoin, Network::Testnet];
+        if !networks.iter().any(|n| n.private_version() == version) || key[0] != 0 {
+            return Err(Bip32Error::InvalidExtendedKey);
+        }
+
+        let mut fingerprint = [0; 4];
+        fingerprint.copy_from_slice(parent_fingerprint);
+        let mut index = [0; 4];
+        index.copy_from_slice(child_number);
+
+        Ok(ExtendedPrivKey {
+            depth,
+            parent_fingerprint: fingerprint,
+            child_number: u32::from_be_bytes(index),
+            chain_code: to_chain_code(chain_code),
+            secret_key: SecretKey::from_slice(&key[1..])
+                .map_err(|_| Bip32Error::InvalidExtendedKey)?,
+        })
+    }
+}
+
+/// Prints only the position in the tree, never the secrets
+impl fmt::Debug for ExtendedPrivKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtendedPrivKey")
+            .field("depth", &self.depth)
+            .field("parent_fingerprint", &hex::encode(self.parent_fingerprint))
+            .field("child_number", &self.child_number)
+            .finish_non_exhaustive()
+    }
+}
+
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> Result<Zeroizing<Vec<u8>>, Bip32Error> {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key)
+        .map_err(|e| Bip32Error::Bip39(Bip39Error::Internal(e.to_string())))?;
+
+    for part in data {
+        mac.update(part);
+    }
+
+    Ok(Zeroizing::new(mac.finalize().into_bytes().to_vec()))
+}
+
+fn to_chain_code(bytes: &[u8]) -> Zeroizing<[u8; 32]> {
+    let mut chain_code = Zeroizing::new([0; 32]);
+    chain_code.copy_from_slice(bytes);
+
+    chain_code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// BIP32 test vector 1
+    fn master() -> ExtendedPrivKey {
+        ExtendedPrivKey::from_seed(&hex::decode("000102030405060708090a0b0c0d0e0f").unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn bip32_test_vector_1() {
+        let master = master();
+
+        assert_eq!(
+            "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi",
+            master.to_xprv(Network::Bitcoin)
+        );
+        assert_eq!(
+            "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8",
+            master.to_xpub(Network::Bitcoin)
+        );
+
+        let key = master.derive(&"m/0'/1/2'".parse().unwrap()).unwrap();
+        assert_eq!(
+            "xprv9z4pot5VBttmtdRTWfWQmoH1taj2axGVzFqSb8C9xaxKymcFzXBDptWmT7FwuEzG3ryjH4ktypQSAewRiNMjANTtpgP4mLTj34bhnZX7UiM",
+            key.to_xprv(Network::Bitcoin)
+        );
+        assert_eq!(
+            "xpub6D4BDPcP2GT577Vvch3R8wDkScZWzQzMMUm3PWbmWvVJrZwQY4VUNgqFJPMM3No2dFDFGTsxxpG5uJh7n7epu4trkrX7x7DogT5Uv6fcLW5",
+            key.to_xpub(Network::Bitcoin)
+        );
+        assert_eq!(3, key.depth());
+        assert_eq!(2 | HARDENED, key.child_number());
+
+        let key = master
+            .derive(&"m/0'/1/2'/2/1000000000".parse().unwrap())
+            .unwrap();
+        assert_eq!(
+            "xprvA41z7zogVVwxVSgdKUHDy1SKmdb533PjDz7J6N6mV6uS3ze1ai8FHa8kmHScGpWmj4WggLyQjgPie1rFSruoUihUZREPSL39UNdE3BBDu76",
+            key.to_xprv(Network::Bitcoin)
+        );
+    }
+
+    #[test]
+    fn xprv_round_trips() {
+        let key = master().derive(&"m/0'/1".parse().unwrap()).unwrap();
+
+        for network in &[Network::Bitcoin, Network::Testnet] {
+            let parsed: ExtendedPrivKey = key.to_xprv(*network).parse().unwrap();
+
+            assert_eq!(key.to_xprv(*network), parsed.to_xprv(*network));
+            assert_eq!(key.depth(), parsed.depth());
+        }
+    }
+
+    #[test]
+    fn invalid_xprv_is_rejected() {
+        let xpub = master().to_xpub(Network::Bitcoin);
+        let mut xprv = master().to_xprv(Network::Bitcoin);
+        // Breaks the checksum
+        xprv.replace_range(10..11, if &xprv[10..11] == "a" { "b" } else { "a" });
+
+        for key in &["", "xprv", xpub.as_str(), xprv.as_str()] {
+            assert_eq!(
+                Some(Bip32Error::InvalidExtendedKey),
+                key.parse::<ExtendedPrivKey>().err()
+            );
+        }
+    }
+
+    #[test]
+    fn fingerprint_is_hash160_prefix() {
+        let master = master();
+
+        assert_eq!("3442193e", hex::encode(master.fingerprint()));
+        assert_eq!(
+            master.fingerprint(),
+            master.child(HARDENED).unwrap().parent_fingerprint
+        );
+    }
+
+    #[test]
+    fn debug_does_not_print_secrets() {
+        let master = master();
+        let printed = format!("{:?}", master);
+
+        assert!(!printed.contains(&hex::encode(&*master.private_key())));
+        assert!(!printed.contains(&hex::encode(&*master.chain_code)));
+    }
+}
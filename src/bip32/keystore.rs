@@ -0,0 +1,326 @@
+//! Ethereum V3 JSON keystore of a derived private key, behind `eth-keystore` feature.
+//!
+//! Key is derived from the password with scrypt, private key is encrypted with AES-128-CTR
+//! under the first half of it, MAC is Keccak-256 of the second half and the ciphertext.
+//! # Resources
+//! https://ethereum.org/en/developers/docs/data-structures-and-encoding/web3-secret-storage/
+use super::ExtendedPrivKey;
+use crate::bip39::Bip39Error;
+use crate::error::WalletError;
+use aes::cipher::{KeyIvInit, StreamCipher};
+use rand::{thread_rng, RngCore};
+use scrypt::{scrypt, Params};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+/// scrypt parameters of "standard" keystores made by geth, N = 2^18
+const SCRYPT_LOG_N: u8 = 18;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+/// Limits of scrypt parameters read from keystores, so a crafted file cannot exhaust
+/// memory or CPU. N = 2^20 takes 128 MiB per r, geth --lightkdf uses r * p = 48.
+const MAX_LOG_N: u8 = 20;
+const MAX_R_TIMES_P: u64 = 64;
+const DKLEN: usize = 32;
+const SALT_LEN: usize = 32;
+const IV_LEN: usize = 16;
+
+#[derive(Serialize, Deserialize)]
+struct Keystore {
+    version: u32,
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    address: String,
+    crypto: Crypto,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Crypto {
+    cipher: String,
+    cipherparams: CipherParams,
+    ciphertext: String,
+    kdf: String,
+    kdfparams: KdfParams,
+    mac: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CipherParams {
+    iv: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KdfParams {
+    dklen: usize,
+    n: u64,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+impl ExtendedPrivKey {
+    /// V3 keystore JSON with the private key encrypted with the password. Salt and IV are
+    /// random, so the same key gives a different keystore every time.
+    pub fn to_eth_keystore(&self, password: &str) -> Result<String, WalletError> {
+        self.eth_keystore(password, SCRYPT_LOG_N)
+    }
+
+    fn eth_keystore(&self, password: &str, log_n: u8) -> Result<String, WalletError> {
+        let mut salt = [0; SALT_LEN];
+        let mut iv = [0; IV_LEN];
+        let mut id = [0; 16];
+        thread_rng().fill_bytes(&mut salt);
+        thread_rng().fill_bytes(&mut iv);
+        thread_rng().fill_bytes(&mut id);
+
+        let key = derive_key(password, &salt, log_n, SCRYPT_R, SCRYPT_P)?;
+
+        let mut ciphertext = self.private_key().to_vec();
+        Aes128Ctr::new(key[..16].into(), &iv.into()).apply_keystream(&mut ciphertext);
+
+        let keystore = Keystore {
+            version: 3,
+            id: uuid_v4(id),
+            address: hex::encode(self.eth_address_bytes()),
+            crypto: Crypto {
+                cipher: "aes-128-ctr".to_string(),
+                cipherparams: CipherParams {
+                    iv: hex::encode(iv),
+                },
+                ciphertext: hex::encode(&ciphertext),
+                kdf: "scrypt".to_string(),
+                kdfparams: KdfParams {
+                    dklen: DKLEN,
+                    n: 1 << log_n,
+                    r: SCRYPT_R,
+                    p: SCRYPT_P,
+                    salt: hex::encode(salt),
+                },
+                mac: hex::encode(mac(&key, &ciphertext)),
+            },
+        };
+
+        Ok(serde_json::to_string(&keystore).map_err(|e| Bip39Error::Internal(e.to_string()))?)
+    }
+}
+
+/// Decrypts private key from V3 keystore JSON, only scrypt and AES-128-CTR ones are supported.
+/// Wrong password and tampered keystore cannot be told apart, both give
+/// `Bip39Error::DecryptionFailed`.
+pub fn from_eth_keystore(json: &str, password: &str) -> Result<Zeroizing<[u8; 32]>, WalletError> {
+    let keystore: Keystore =
+        serde_json::from_str(json).map_err(|e| Bip39Error::InvalidBackup(e.to_string()))?;
+    let crypto = &keystore.crypto;
+
+    if keystore.version != 3 {
+        return Err(invalid(&format!(
+            "unsupported version {}",
+            keystore.version
+        )));
+    }
+    if crypto.kdf != "scrypt" || crypto.cipher != "aes-128-ctr" {
+        return Err(invalid(&format!(
+            "unsupported {} with {}",
+            crypto.kdf, crypto.cipher
+        )));
+    }
+
+    let params = &crypto.kdfparams;
+    if !params.n.is_power_of_two() || params.n < 2 || params.dklen != DKLEN {
+        return Err(invalid("unsupported scrypt parameters"));
+    }
+
+    let log_n = params.n.trailing_zeros() as u8;
+    if log_n > MAX_LOG_N || u64::from(params.r) * u64::from(params.p) > MAX_R_TIMES_P {
+        return Err(invalid("scrypt parameters too expensive"));
+    }
+
+    let salt = decode(&params.salt, None)?;
+    let iv = decode(&crypto.cipherparams.iv, Some(IV_LEN))?;
+    let mut ciphertext = Zeroizing::new(decode(&crypto.ciphertext, Some(32))?);
+    let expected_mac = decode(&crypto.mac, Some(32))?;
+
+    let key = derive_key(password, &salt, log_n, params.r, params.p)?;
+
+    if !bool::from(mac(&key, &ciphertext).ct_eq(&expected_mac[..])) {
+        return Err(Bip39Error::DecryptionFailed.into());
+    }
+
+    Aes128Ctr::new(key[..16].into(), iv[..].into()).apply_keystream(&mut ciphertext);
+
+    let mut private_key = Zeroizing::new([0; 32]);
+    private_key.copy_from_slice(&ciphertext);
+
+    Ok(private_key)
+}
+
+fn derive_key(
+    password: &str,
+    salt: &[u8],
+    log_n: u8,
+    r: u32,
+    p: u32,
+) -> Result<Zeroizing<[u8; DKLEN]>, Bip39Error> {
+    let params = Params::new(log_n, r, p, DKLEN)
+        .map_err(|e| Bip39Error::InvalidBackup(format!("scrypt parameters: {}", e)))?;
+    let mut key = Zeroizing::new([0; DKLEN]);
+
+    scrypt(password.as_bytes(), salt, &params, &mut *key)
+        .map_err(|e| Bip39Error::Internal(e.to_string()))?;
+
+    Ok(key)
+}
+
+fn mac(key: &[u8; DKLEN], ciphertext: &[u8]) -> [u8; 32] {
+    Keccak256::new()
+        .chain_update(&key[16..])
+        .chain_update(ciphertext)
+        .finalize()
+        .into()
+}
+
+/// Random (version 4) UUID, e.g. "3198bc9c-6672-4ab3-8d95-8e6b8d4f5b7c"
+fn uuid_v4(mut bytes: [u8; 16]) -> String {
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+fn decode(field: &str, len: Option<usize>) -> Result<Vec<u8>, Bip39Error> {
+    let bytes = hex::decode(field).map_err(|e| Bip39Error::InvalidBackup(e.to_string()))?;
+
+    match len {
+        Some(len) if bytes.len() != len => Err(Bip39Error::InvalidBackup(format!(
+            "expected {} bytes, got {}",
+            len,
+            bytes.len()
+        ))),
+        _ => Ok(bytes),
+    }
+}
+
+fn invalid(reason: &str) -> WalletError {
+    Bip39Error::InvalidBackup(reason.to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip32::Wallet;
+    use crate::bip39::abandon_about;
+    use serde_json::Value;
+
+    fn key() -> ExtendedPrivKey {
+        Wallet::new(&abandon_about(), "")
+            .unwrap()
+            .derive(&"m/44'/60'/0'/0/0".parse().unwrap())
+            .unwrap()
+    }
+
+    /// N = 2^12, as geth --lightkdf, standard N = 2^18 takes tens of seconds in debug builds
+    const LIGHT_LOG_N: u8 = 12;
+
+    #[test]
+    fn keystore_round_trips() {
+        let key = key();
+
+        let json = key.eth_keystore("password", LIGHT_LOG_N).unwrap();
+
+        let keystore: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(3, keystore["version"]);
+        assert_eq!(
+            "9858effd232b4033e47d90003d41ec34ecaeda94",
+            keystore["address"]
+        );
+        assert_eq!(4096, keystore["crypto"]["kdfparams"]["n"]);
+        assert_eq!(
+            '4',
+            keystore["id"].as_str().unwrap().chars().nth(14).unwrap()
+        );
+
+        assert_eq!(
+            key.private_key(),
+            from_eth_keystore(&json, "password").unwrap()
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn keystore_with_standard_parameters_round_trips() {
+        let key = key();
+
+        let json = key.to_eth_keystore("password").unwrap();
+
+        let keystore: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(262_144, keystore["crypto"]["kdfparams"]["n"]);
+        assert_eq!(
+            key.private_key(),
+            from_eth_keystore(&json, "password").unwrap()
+        );
+    }
+
+    #[test]
+    fn wrong_password_fails_mac_check() {
+        let json = key().eth_keystore("password", LIGHT_LOG_N).unwrap();
+
+        assert_eq!(
+            Some(WalletError::Bip39(Bip39Error::DecryptionFailed)),
+            from_eth_keystore(&json, "Password").err()
+        );
+    }
+
+    #[test]
+    fn unsupported_keystore_is_rejected() {
+        let json = key().eth_keystore("password", LIGHT_LOG_N).unwrap();
+        let pbkdf2 = json.replace("\"scrypt\"", "\"pbkdf2\"");
+
+        assert_eq!(
+            Some(WalletError::Bip39(Bip39Error::InvalidBackup(
+                "unsupported pbkdf2 with aes-128-ctr".to_string()
+            ))),
+            from_eth_keystore(&pbkdf2, "password").err()
+        );
+        assert!(matches!(
+            from_eth_keystore("{}", "password"),
+            Err(WalletError::Bip39(Bip39Error::InvalidBackup(_)))
+        ));
+    }
+
+    #[test]
+    fn expensive_scrypt_parameters_are_rejected() {
+        let json = key().eth_keystore("password", LIGHT_LOG_N).unwrap();
+        let too_expensive = Some(WalletError::Bip39(Bip39Error::InvalidBackup(
+            "scrypt parameters too expensive".to_string(),
+        )));
+
+        for (n, r, p) in &[
+            (1u64 << 21, 8, 1),
+            (1 << 12, 8, 9),
+            (1 << 12, u32::MAX, u32::MAX),
+        ] {
+            let mut keystore: Value = serde_json::from_str(&json).unwrap();
+            keystore["crypto"]["kdfparams"]["n"] = (*n).into();
+            keystore["crypto"]["kdfparams"]["r"] = (*r).into();
+            keystore["crypto"]["kdfparams"]["p"] = (*p).into();
+
+            assert_eq!(
+                too_expensive,
+                from_eth_keystore(&keystore.to_string(), "password").err()
+            );
+        }
+    }
+}
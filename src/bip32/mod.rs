@@ -0,0 +1,28 @@
+mod address;
+mod bip85;
+mod coin;
+mod config;
+mod derivation_path;
+mod export;
+mod extended_key;
+#[cfg(feature = "eth-keystore")]
+mod keystore;
+mod password;
+mod root_key;
+mod sign;
+#[cfg(feature = "taproot")]
+mod taproot;
+mod wallet;
+pub use address::*;
+pub use bip85::*;
+pub use coin::*;
+pub use config::*;
+pub use derivation_path::*;
+pub use export::*;
+pub use extended_key::*;
+#[cfg(feature = "eth-keystore")]
+pub use keystore::*;
+pub use password::*;
+pub use root_key::*;
+pub use sign::*;
+pub use wallet::*;
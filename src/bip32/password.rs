@@ -0,0 +1,155 @@
+//! Deterministic per-site passwords, a non-standard BIP85 application, so the mnemonic
+//! backup restores all the passwords as well
+use super::{bip85_entropy, ExtendedPrivKey, HARDENED};
+use crate::bip39::Bip39Error;
+use crate::error::WalletError;
+use hmac::{Hmac, Mac, NewMac};
+use sha2::{Digest, Sha256, Sha512};
+use zeroize::Zeroizing;
+
+/// Application number of site passwords, "pw" in ASCII. Not assigned by BIP85,
+/// its own password applications do not take a site.
+const PASSWORD_APP: u32 = 28_791;
+
+/// Characters the password is made of
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Charset {
+    /// 0-9
+    Digits,
+    /// a-z, A-Z, 0-9
+    Alphanumeric,
+    /// Printable ASCII without space, for sites requiring special characters
+    Printable,
+}
+
+impl Charset {
+    fn characters(&self) -> Vec<u8> {
+        match self {
+            Charset::Digits => (b'0'..=b'9').collect(),
+            Charset::Alphanumeric => (b'a'..=b'z')
+                .chain(b'A'..=b'Z')
+                .chain(b'0'..=b'9')
+                .collect(),
+            Charset::Printable => (b'!'..=b'~').collect(),
+        }
+    }
+}
+
+impl ExtendedPrivKey {
+    /// Password of the site, from BIP85 entropy at m/83696968'/28791'/site'/index', has to be
+    /// called on the master key. Site index is the first 31 bits of SHA256 of the site,
+    /// bump `index` to rotate the password. Characters are picked uniformly, bytes of
+    /// HMAC-SHA512 stream keyed with the entropy are rejected if they would bias the pick.
+    pub fn derive_password(
+        &self,
+        site: &str,
+        index: u32,
+        length: usize,
+        charset: Charset,
+    ) -> Result<String, WalletError> {
+        let entropy = Zeroizing::new(bip85_entropy(
+            self,
+            PASSWORD_APP,
+            &[site_index(site), index],
+        )?);
+        let characters = charset.characters();
+        // Largest multiple of the charset size, bytes above it are rejected
+        let limit = 256 - 256 % characters.len();
+
+        let mut password = Zeroizing::new(String::with_capacity(length));
+        let mut counter: u32 = 0;
+        while password.len() < length {
+            let mut mac = Hmac::<Sha512>::new_from_slice(&entropy)
+                .map_err(|e| Bip39Error::Internal(e.to_string()))?;
+            mac.update(&counter.to_be_bytes());
+            counter += 1;
+
+            for &byte in mac.finalize().into_bytes().iter() {
+                if password.len() == length {
+                    break;
+                }
+                if usize::from(byte) < limit {
+                    password.push(characters[usize::from(byte) % characters.len()].into());
+                }
+            }
+        }
+
+        Ok(password.to_string())
+    }
+}
+
+fn site_index(site: &str) -> u32 {
+    let hash = Sha256::digest(site.as_bytes());
+
+    u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]) & !HARDENED
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip32::Bip32Error;
+
+    /// Master key of BIP85 test vectors
+    fn master() -> ExtendedPrivKey {
+        "xprv9s21ZrQH143K2LBWUUQRFXhucrQqBpKdRRxNVq2zBqsx8HVqFk2uYo8kmbaLLHRdqtQpUm98uKfu3vca1LqdGhUtyoFnCNkfmXRyPXLjbKb"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn same_site_gives_same_password() {
+        let master = master();
+
+        let password = master
+            .derive_password("example.com", 0, 20, Charset::Alphanumeric)
+            .unwrap();
+
+        assert_eq!(20, password.len());
+        assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_eq!(
+            password,
+            master
+                .derive_password("example.com", 0, 20, Charset::Alphanumeric)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn site_and_index_change_password() {
+        let master = master();
+        let password = |site, index| {
+            master
+                .derive_password(site, index, 16, Charset::Printable)
+                .unwrap()
+        };
+
+        assert_ne!(password("example.com", 0), password("example.org", 0));
+        assert_ne!(password("example.com", 0), password("example.com", 1));
+    }
+
+    #[test]
+    fn password_uses_charset() {
+        let pin = master()
+            .derive_password("bank", 0, 200, Charset::Digits)
+            .unwrap();
+
+        assert_eq!(200, pin.len());
+        assert!(pin.chars().all(|c| c.is_ascii_digit()));
+
+        let printable = master()
+            .derive_password("bank", 0, 200, Charset::Printable)
+            .unwrap();
+        assert!(printable.chars().all(|c| c.is_ascii_graphic()));
+        assert!(printable.chars().any(|c| c.is_ascii_punctuation()));
+    }
+
+    #[test]
+    fn too_large_index_is_rejected() {
+        assert_eq!(
+            Err(WalletError::Bip32(Bip32Error::PathIndexTooLarge(
+                HARDENED.to_string()
+            ))),
+            master().derive_password("example.com", HARDENED, 16, Charset::Digits)
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! BIP32 master (root) key, as shown by reference tools in the "BIP32 Root Key" field.
+use super::{Bip32Error, DerivationPath, ExtendedPrivKey, HARDENED};
+use crate::bip39::{Mnemonic, Passphrase, Seed};
+use zeroize::Zeroizing;
+
+/// Network the extended key is serialized for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Network {
+    Bitcoin,
+    Testnet,
+}
+
+impl Network {
+    /// Network of the keys under BIP44-style path, testnet for coin type 1', mainnet otherwise
+    pub fn from_path(path: &DerivationPath) -> Network {
+        match path.indices().get(1) {
+            Some(&index) if index == 1 | HARDENED => Network::Testnet,
+            _ => Network::Bitcoin,
+        }
+    }
+
+    /// Version bytes of the serialized extended private key, "xprv" and "tprv"
+    pub(super) fn private_version(&self) -> [u8; 4] {
+        match self {
+            Network::Bitcoin => [0x04, 0x88, 0xad, 0xe4],
+            Network::Testnet => [0x04, 0x35, 0x83, 0x94],
+        }
+    }
+
+    /// Version bytes of the serialized extended public key, "xpub" and "tpub"
+    pub(super) fn public_version(&self) -> [u8; 4] {
+        match self {
+            Network::Bitcoin => [0x04, 0x88, 0xb2, 0x1e],
+            Network::Testnet => [0x04, 0x35, 0x87, 0xcf],
+        }
+    }
+
+    /// Coin type of BIP44 paths, m/44'/coin_type'/..., testnet of every coin shares 1'
+    pub(super) fn coin_type(&self) -> u32 {
+        match self {
+            Network::Bitcoin => 0,
+            Network::Testnet => 1,
+        }
+    }
+
+    /// Version byte of base58 P2PKH addresses, "1..." and "m..." or "n..."
+    pub(super) fn p2pkh_version(&self) -> u8 {
+        match self {
+            Network::Bitcoin => 0x00,
+            Network::Testnet => 0x6f,
+        }
+    }
+
+    /// Version byte of base58 P2SH addresses, "3..." and "2..."
+    pub(super) fn p2sh_version(&self) -> u8 {
+        match self {
+            Network::Bitcoin => 0x05,
+            Network::Testnet => 0xc4,
+        }
+    }
+
+    /// Version byte of WIF private keys
+    pub(super) fn wif_version(&self) -> u8 {
+        match self {
+            Network::Bitcoin => 0x80,
+            Network::Testnet => 0xef,
+        }
+    }
+
+    /// Human readable part of bech32 SegWit addresses
+    pub(super) fn hrp(&self) -> &'static str {
+        match self {
+            Network::Bitcoin => "bc",
+            Network::Testnet => "tb",
+        }
+    }
+}
+
+/// Serializes master key derived from the seed, base58check encoded xprv (tprv) at depth 0
+pub fn seed_to_root_key(seed: &[u8], network: Network) -> Result<String, Bip32Error> {
+    Ok(ExtendedPrivKey::from_seed(seed)?.to_xprv(network))
+}
+
+/// Master key fingerprint, identifies the seed without storing it, e.g. "73c5da0a"
+/// as shown by most wallets when hex encoded
+pub fn seed_fingerprint(seed: &Seed) -> Result<[u8; 4], Bip32Error> {
+    Ok(ExtendedPrivKey::from_seed(seed)?.fingerprint())
+}
+
+/// Mnemonic to "BIP32 Root Key" in one call: seed derivation, master key, and serialization.
+/// Takes `Mnemonic`, so root key is never derived from a phrase that was not validated.
+pub fn bip32_root_key(
+    mnemonic: &Mnemonic,
+    passphrase: &str,
+    network: Network,
+) -> Result<String, Bip32Error> {
+    let seed = Zeroizing::new(mnemonic.to_seed(&Passphrase::new(passphrase)));
+
+    seed_to_root_key(&seed, network)
+}
+
+/// Orders recovered mnemonics candidates, e.g. from `recover_phrase`, so the ones giving
+/// the known root key go first. Other candidates keep their order.
+pub fn rank_by_root_key(
+    candidates: Vec<Mnemonic>,
+    passphrase: &str,
+    root_key: &str,
+    network: Network,
+) -> Result<Vec<Mnemonic>, Bip32Error> {
+    let mut matching = vec![];
+    let mut rest = vec![];
+
+    for candidate in candidates {
+        if bip32_root_key(&candidate, passphrase, network)? == root_key {
+            matching.push(candidate);
+        } else {
+            rest.push(candidate);
+        }
+    }
+
+    matching.append(&mut rest);
+
+    Ok(matching)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::{abandon_about, recover_phrase};
+
+    #[test]
+    fn bip32_root_key_works() {
+        // Same as in BIP84 test vectors
+        assert_eq!(
+            "xprv9s21ZrQH143K3GJpoapnV8SFfukcVBSfeCficPSGfubmSFDxo1kuHnLisriDvSnRRuL2Qrg5ggqHKNVpxR86QEC8w35uxmGoggxtQTPvfUu",
+            bip32_root_key(&abandon_about(), "", Network::Bitcoin).unwrap()
+        );
+
+        // From vectors.json
+        assert_eq!(
+            "xprv9s21ZrQH143K3h3fDYiay8mocZ3afhfULfb5GX8kCBdno77K4HiA15Tg23wpbeF1pLfs1c5SPmYHrEpTuuRhxMwvKDwqdKiGJS9XFKzUsAF",
+            bip32_root_key(&abandon_about(), "TREZOR", Network::Bitcoin).unwrap()
+        );
+    }
+
+    #[test]
+    fn seed_fingerprint_of_test_mnemonic() {
+        let seed = abandon_about().to_seed(&Passphrase::new(""));
+
+        assert_eq!("73c5da0a", hex::encode(seed_fingerprint(&seed).unwrap()));
+
+        let seed = abandon_about().to_seed(&Passphrase::new("TREZOR"));
+        assert_ne!("73c5da0a", hex::encode(seed_fingerprint(&seed).unwrap()));
+    }
+
+    #[test]
+    fn rank_by_root_key_puts_matching_first() {
+        // Deriving root key for all 128 candidates takes a while, few first are enough,
+        // reversed so the matching one is not already first
+        let mut candidates = recover_phrase(&format!("{}?", "abandon ".repeat(11))).unwrap();
+        candidates.truncate(8);
+        candidates.reverse();
+        assert_ne!(abandon_about(), candidates[0]);
+        assert!(candidates.contains(&abandon_about()));
+
+        let root_key = bip32_root_key(&abandon_about(), "", Network::Bitcoin).unwrap();
+
+        let ranked = rank_by_root_key(candidates.clone(), "", &root_key, Network::Bitcoin).unwrap();
+
+        assert_eq!(candidates.len(), ranked.len());
+        assert_eq!(abandon_about(), ranked[0]);
+        assert_ne!(abandon_about(), ranked[1]);
+    }
+
+    #[test]
+    fn testnet_root_key_has_tprv_prefix() {
+        let key = bip32_root_key(&abandon_about(), "", Network::Testnet).unwrap();
+
+        assert!(key.starts_with("tprv"));
+    }
+}
@@ -0,0 +1,81 @@
+//! ECDSA signatures of transaction sighashes with derived keys. Assembling the transaction
+//! and computing its sighash is up to the caller.
+use super::ExtendedPrivKey;
+use crate::bip39::Bip39Error;
+use crate::error::WalletError;
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use k256::ecdsa::{Signature, SigningKey};
+
+/// Signs whole transaction, the most common sighash type
+pub const SIGHASH_ALL: u8 = 0x01;
+
+impl ExtendedPrivKey {
+    /// DER encoded signature of the sighash with the sighash type byte appended, as it goes
+    /// to scriptSig or witness. Nonce is deterministic (RFC6979), S is low (BIP62).
+    pub fn sign_sighash(
+        &self,
+        sighash: &[u8; 32],
+        sighash_type: u8,
+    ) -> Result<Vec<u8>, WalletError> {
+        let signing_key = SigningKey::from_slice(&*self.private_key())
+            .map_err(|e| Bip39Error::Internal(e.to_string()))?;
+        let signature: Signature = signing_key
+            .sign_prehash(sighash)
+            .map_err(|e| Bip39Error::Internal(e.to_string()))?;
+        let signature = signature.normalize_s().unwrap_or(signature);
+
+        let mut signature = signature.to_der().as_bytes().to_vec();
+        signature.push(sighash_type);
+
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip32::Wallet;
+    use crate::bip39::abandon_about;
+    use k256::ecdsa::signature::hazmat::PrehashVerifier;
+    use k256::ecdsa::VerifyingKey;
+
+    fn key() -> ExtendedPrivKey {
+        Wallet::new(&abandon_about(), "")
+            .unwrap()
+            .derive(&"m/84'/0'/0'/0/0".parse().unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn signature_is_valid_der_with_sighash_type() {
+        let key = key();
+        let sighash = [0x42; 32];
+
+        let signature = key.sign_sighash(&sighash, SIGHASH_ALL).unwrap();
+
+        let (&sighash_type, der) = signature.split_last().unwrap();
+        assert_eq!(SIGHASH_ALL, sighash_type);
+
+        let parsed = Signature::from_der(der).unwrap();
+        assert!(parsed.normalize_s().is_none(), "S is not low");
+
+        let verifying_key = VerifyingKey::from_sec1_bytes(&key.public_key()).unwrap();
+        assert!(verifying_key.verify_prehash(&sighash, &parsed).is_ok());
+        assert!(verifying_key.verify_prehash(&[0x43; 32], &parsed).is_err());
+    }
+
+    #[test]
+    fn signature_is_deterministic() {
+        let key = key();
+        let sighash = [7; 32];
+
+        assert_eq!(
+            key.sign_sighash(&sighash, SIGHASH_ALL).unwrap(),
+            key.sign_sighash(&sighash, SIGHASH_ALL).unwrap()
+        );
+        assert_eq!(
+            Some(&0x83),
+            key.sign_sighash(&sighash, 0x83).unwrap().last()
+        );
+    }
+}
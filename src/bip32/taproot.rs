@@ -0,0 +1,95 @@
+//! Taproot key path addresses of derived keys (BIP86), behind `taproot` feature.
+//! Output key is the internal key tweaked with its own hash, there is no script tree.
+//! # Resources
+//! https://github.com/bitcoin/bips/blob/master/bip-0086.mediawiki
+//! https://github.com/bitcoin/bips/blob/master/bip-0341.mediawiki
+use super::{segwit_address, Bip32Error, ExtendedPrivKey, Network};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::elliptic_curve::PrimeField;
+use k256::{ProjectivePoint, PublicKey, Scalar};
+use sha2::{Digest, Sha256};
+
+impl ExtendedPrivKey {
+    /// Taproot address of this key, e.g. the first BIP86 receive address for m/86'/0'/0'/0/0.
+    /// Internal key is the x-only public key, tweaked by BIP341 TapTweak hash with empty
+    /// merkle root, witness version 1 is encoded with bech32m.
+    pub fn p2tr_address(&self, network: Network) -> Result<String, Bip32Error> {
+        let public_key = self.public_key();
+        let internal_key = &public_key[1..];
+
+        let tweak: Option<Scalar> =
+            Scalar::from_repr(tagged_hash(b"TapTweak", internal_key).into()).into();
+        let tweak = tweak.ok_or(Bip32Error::InvalidTweak)?;
+
+        // x-only key stands for the point with even y, 0x02 prefix
+        let point = PublicKey::from_sec1_bytes(&public_key)
+            .map_err(|_| Bip32Error::InvalidTweak)?
+            .to_projective();
+        let point = if public_key[0] == 0x03 { -point } else { point };
+
+        let output_key = point + ProjectivePoint::GENERATOR * tweak;
+        if output_key == ProjectivePoint::IDENTITY {
+            return Err(Bip32Error::InvalidTweak);
+        }
+
+        let output_key = output_key.to_affine().to_encoded_point(true);
+
+        Ok(segwit_address(network, 1, &output_key.as_bytes()[1..]))
+    }
+}
+
+/// BIP340 tagged hash, SHA256(SHA256(tag) || SHA256(tag) || data)
+fn tagged_hash(tag: &[u8], data: &[u8]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag);
+
+    Sha256::new()
+        .chain(tag_hash)
+        .chain(tag_hash)
+        .chain(data)
+        .finalize()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::{abandon_about, Passphrase};
+
+    fn derive(path: &str) -> ExtendedPrivKey {
+        ExtendedPrivKey::from_seed(&abandon_about().to_seed(&Passphrase::new("")))
+            .unwrap()
+            .derive(&path.parse().unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn bip86_test_vectors() {
+        for (path, address) in &[
+            (
+                "m/86'/0'/0'/0/0",
+                "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            ),
+            (
+                "m/86'/0'/0'/0/1",
+                "bc1p4qhjn9zdvkux4e44uhx8tc55attvtyu358kutcqkudyccelu0was9fqzwh",
+            ),
+            (
+                "m/86'/0'/0'/1/0",
+                "bc1p3qkhfews2uk44qtvauqyr2ttdsw7svhkl9nkm9s9c3x4ax5h60wqwruhk7",
+            ),
+        ] {
+            assert_eq!(
+                *address,
+                derive(path).p2tr_address(Network::Bitcoin).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn testnet_address_has_testnet_prefix() {
+        assert!(derive("m/86'/1'/0'/0/0")
+            .p2tr_address(Network::Testnet)
+            .unwrap()
+            .starts_with("tb1p"));
+    }
+}
@@ -0,0 +1,764 @@
+//! HD wallet, master key of a seed with keys and exports of its accounts
+use super::{AddressType, Bip32Error, DerivationPath, ExtendedPrivKey, Network, HARDENED};
+use crate::bip39::{Mnemonic, Passphrase};
+use crate::error::WalletError;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::convert::TryFrom;
+use zeroize::Zeroizing;
+
+/// Number of intermediate nodes cached by `Wallet`
+const CACHE_SIZE: usize = 16;
+
+/// Purpose of multisig accounts, m/48'/coin_type'/account'/script_type'
+const MULTISIG_PURPOSE: u32 = 48;
+
+/// Master key, all the other keys of the wallet are derived from it
+#[derive(Debug, Clone)]
+pub struct Wallet {
+    master: ExtendedPrivKey,
+    /// Parents of recently derived keys, keyed by their path indices, least recently used
+    /// first. Siblings, e.g. consecutive addresses, derive only the last index then.
+    cache: RefCell<VecDeque<(Vec<u32>, ExtendedPrivKey)>>,
+}
+
+impl Wallet {
+    /// Wallet of the mnemonic, seed is derived with the passphrase, "" if there is none
+    pub fn new(mnemonic: &Mnemonic, passphrase: &str) -> Result<Self, WalletError> {
+        let seed = Zeroizing::new(mnemonic.to_seed(&Passphrase::new(passphrase)));
+
+        Wallet::from_seed(&seed)
+    }
+
+    /// Wallet of the BIP39 seed
+    pub fn from_seed(seed: &[u8]) -> Result<Self, WalletError> {
+        Ok(Wallet {
+            master: ExtendedPrivKey::from_seed(seed)?,
+            cache: RefCell::new(VecDeque::with_capacity(CACHE_SIZE)),
+        })
+    }
+
+    pub fn master(&self) -> &ExtendedPrivKey {
+        &self.master
+    }
+
+    /// Master key fingerprint, identifies the wallet, e.g. in key origins of descriptors
+    pub fn fingerprint(&self) -> [u8; 4] {
+        self.master.fingerprint()
+    }
+
+    /// Key at the path from the master key. Its parent is cached, and the parent itself
+    /// is derived from the longest cached prefix of the path.
+    pub fn derive(&self, path: &DerivationPath) -> Result<ExtendedPrivKey, WalletError> {
+        match path.indices().split_last() {
+            Some((&index, parent)) => Ok(self.cached_node(parent)?.child(index)?),
+            None => Ok(self.master.clone()),
+        }
+    }
+
+    /// Drops cached intermediate keys, their secrets are zeroized
+    pub fn clear_cache(&mut self) {
+        self.cache.get_mut().clear();
+    }
+
+    /// Node at the path indices, derived from the longest cached prefix, and cached itself
+    fn cached_node(&self, indices: &[u32]) -> Result<ExtendedPrivKey, Bip32Error> {
+        if indices.is_empty() {
+            return Ok(self.master.clone());
+        }
+
+        let mut cache = self.cache.borrow_mut();
+        let prefix = cache
+            .iter()
+            .enumerate()
+            .filter(|(_, (prefix, _))| indices.starts_with(prefix))
+            .max_by_key(|(_, (prefix, _))| prefix.len())
+            .map(|(position, (prefix, _))| (position, prefix.len()));
+
+        let node = match prefix {
+            Some((position, depth)) if depth == indices.len() => {
+                let entry = cache.remove(position).expect("Position of existing entry");
+                let node = entry.1.clone();
+                cache.push_back(entry);
+
+                return Ok(node);
+            }
+            Some((position, depth)) => cache[position]
+                .1
+                .derive(&DerivationPath::from(indices[depth..].to_vec()))?,
+            None => self
+                .master
+                .derive(&DerivationPath::from(indices.to_vec()))?,
+        };
+
+        if cache.len() == CACHE_SIZE {
+            cache.pop_front();
+        }
+        cache.push_back((indices.to_vec(), node.clone()));
+
+        Ok(node)
+    }
+
+    /// Keys at all the paths, keyed by the path in canonical form, e.g. "m/84'/0'/0'".
+    /// Shared prefixes of the paths are derived only once.
+    pub fn derive_many(
+        &self,
+        paths: &[DerivationPath],
+    ) -> Result<BTreeMap<String, ExtendedPrivKey>, WalletError> {
+        Ok(self.derive_shared(paths)?.0)
+    }
+
+    /// `derive_many`, with the number of child derivations it took
+    fn derive_shared(
+        &self,
+        paths: &[DerivationPath],
+    ) -> Result<(BTreeMap<String, ExtendedPrivKey>, usize), WalletError> {
+        // Every node derived so far, keyed by its path indices, the master key is the empty path
+        let mut nodes: HashMap<Vec<u32>, ExtendedPrivKey> = HashMap::new();
+        nodes.insert(vec![], self.master.clone());
+        let mut derivations = 0;
+        let mut keys = BTreeMap::new();
+
+        for path in paths {
+            let indices = path.indices();
+            let known = (0..=indices.len())
+                .rev()
+                .find(|&depth| nodes.contains_key(&indices[..depth]))
+                .unwrap_or(0);
+
+            for depth in known..indices.len() {
+                let child = nodes[&indices[..depth]].child(indices[depth])?;
+                derivations += 1;
+                nodes.insert(indices[..=depth].to_vec(), child);
+            }
+
+            keys.insert(path.to_string(), nodes[indices].clone());
+        }
+
+        Ok((keys, derivations))
+    }
+
+    /// First `count` addresses under the path, e.g. receive addresses of the first native
+    /// SegWit account for m/84'/0'/0'/0, as needed by gap-limit scans.
+    /// Address type and network are inferred from the path, the path is derived only once.
+    pub fn scan_addresses(
+        &self,
+        account_path: &DerivationPath,
+        count: usize,
+    ) -> Result<Vec<String>, WalletError> {
+        let address_type = AddressType::from_path(account_path)?;
+        let network = Network::from_path(account_path);
+        let parent = self.derive(account_path)?;
+
+        (0..count)
+            .map(|index| {
+                let index = u32::try_from(index)
+                    .ok()
+                    .filter(|&index| index < HARDENED)
+                    .ok_or_else(|| Bip32Error::PathIndexTooLarge(index.to_string()))?;
+
+                Ok(parent.child(index)?.address(address_type, network)?)
+            })
+            .collect()
+    }
+
+    /// Receive address of the first account, m/purpose'/coin_type'/0'/0/index
+    pub fn receive_address(
+        &self,
+        address_type: AddressType,
+        network: Network,
+        index: u32,
+    ) -> Result<String, WalletError> {
+        let path = address_type.account_path(0, network)?.child(0).child(index);
+
+        Ok(self.derive(&path)?.address(address_type, network)?)
+    }
+
+    /// Printable sheet with everything needed to verify and restore the wallet: numbered
+    /// words, master fingerprint, path and the first receive address. Mnemonic and passphrase
+    /// have to restore this wallet, otherwise `FingerprintMismatch`. Passphrase is never printed.
+    pub fn recovery_sheet(
+        &self,
+        m: &Mnemonic,
+        passphrase: &str,
+        address_type: AddressType,
+        network: Network,
+    ) -> Result<String, WalletError> {
+        let found = Wallet::new(m, passphrase)?.fingerprint();
+        if found != self.fingerprint() {
+            return Err(Bip32Error::FingerprintMismatch {
+                expected: hex::encode(self.fingerprint()),
+                found: hex::encode(found),
+            }
+            .into());
+        }
+
+        let path = address_type.account_path(0, network)?.child(0).child(0);
+        let address = self.derive(&path)?.address(address_type, network)?;
+
+        let mut sheet = String::new();
+        for (i, word) in m.words().iter().enumerate() {
+            sheet.push_str(&format!("{:>2}. {}\n", i + 1, word));
+        }
+        sheet.push_str(&format!(
+            "\nMaster fingerprint: {}\nDerivation path: {}\nFirst receive address: {}\n",
+            hex::encode(self.fingerprint()),
+            path,
+            address
+        ));
+
+        Ok(sheet)
+    }
+
+    /// Output descriptor of the account receive addresses, with the key origin,
+    /// e.g. `wpkh([73c5da0a/84h/0h/0h]xpub.../0/*)`.
+    /// Account key is always xpub (tpub), descriptors do not use SLIP-132 versions.
+    pub fn descriptor(
+        &self,
+        address_type: AddressType,
+        account: u32,
+        network: Network,
+    ) -> Result<String, WalletError> {
+        let path = address_type.account_path(account, network)?;
+        let key = format!(
+            "[{}{}]{}/0/*",
+            hex::encode(self.fingerprint()),
+            path.to_string().trim_start_matches('m').replace('\'', "h"),
+            self.derive(&path)?.to_xpub(network)
+        );
+
+        Ok(match address_type {
+            AddressType::Legacy => format!("pkh({})", key),
+            AddressType::P2shSegwit => format!("sh(wpkh({}))", key),
+            AddressType::NativeSegwit => format!("wpkh({})", key),
+            #[cfg(feature = "taproot")]
+            AddressType::Taproot => format!("tr({})", key),
+        })
+    }
+
+    /// Key origin record exchanged by multisig coordinators, e.g. `[73c5da0a/48'/0'/0'/2']Zpub...`.
+    /// Key at the path uses the SLIP-132 version of its script type, multisig paths
+    /// m/48'/.../1' and m/48'/.../2' give "Ypub" and "Zpub", the other ones follow their purpose.
+    pub fn key_origin(
+        &self,
+        path: &DerivationPath,
+        network: Network,
+    ) -> Result<String, WalletError> {
+        Ok(format!(
+            "[{}{}]{}",
+            hex::encode(self.fingerprint()),
+            path.to_string().trim_start_matches('m'),
+            self.derive(path)?
+                .serialize_public(slip132_public_version(path, network))
+        ))
+    }
+}
+
+/// SLIP-132 version of extended public keys at the path, multisig script types included
+fn slip132_public_version(path: &DerivationPath, network: Network) -> [u8; 4] {
+    let indices = path.indices();
+    if indices.first() != Some(&(MULTISIG_PURPOSE | HARDENED)) {
+        return AddressType::from_path(path)
+            .map_or_else(|_| network.public_version(), |t| t.public_version(network));
+    }
+
+    match (indices.get(3).map(|index| index & !HARDENED), network) {
+        // P2WSH nested in P2SH
+        (Some(1), Network::Bitcoin) => [0x02, 0x95, 0xb4, 0x3f],
+        (Some(1), Network::Testnet) => [0x02, 0x42, 0x89, 0xef],
+        // P2WSH
+        (Some(2), Network::Bitcoin) => [0x02, 0xaa, 0x7e, 0xd3],
+        (Some(2), Network::Testnet) => [0x02, 0x57, 0x54, 0x83],
+        _ => network.public_version(),
+    }
+}
+
+impl Mnemonic {
+    /// Wallet of every passphrase, "hidden wallets" of the same mnemonic, in the same order.
+    /// Seed is derived once per passphrase.
+    pub fn hidden_wallets(&self, passphrases: &[&str]) -> Result<Vec<Wallet>, WalletError> {
+        passphrases
+            .iter()
+            .map(|passphrase| Wallet::new(self, passphrase))
+            .collect()
+    }
+}
+
+/// Checks the address the user remembers against the key at the path, "is this my seed?".
+/// Address type is inferred from the purpose of the path, as in `Wallet::scan_addresses`.
+pub fn mnemonic_controls_address(
+    m: &Mnemonic,
+    passphrase: &str,
+    path: &DerivationPath,
+    address: &str,
+    network: Network,
+) -> Result<bool, WalletError> {
+    let key = Wallet::new(m, passphrase)?.derive(path)?;
+
+    Ok(key.address(AddressType::from_path(path)?, network)? == address)
+}
+
+/// Orders recovered mnemonics candidates, e.g. from `recover_phrase`, so the ones controlling
+/// the known address at the path go first. Other candidates keep their order.
+pub fn rank_by_address(
+    candidates: Vec<Mnemonic>,
+    passphrase: &str,
+    path: &DerivationPath,
+    address: &str,
+    network: Network,
+) -> Result<Vec<Mnemonic>, WalletError> {
+    rank_by(candidates, |candidate| {
+        mnemonic_controls_address(candidate, passphrase, path, address, network)
+    })
+}
+
+/// Orders recovered mnemonics candidates so the ones giving the known extended public key
+/// at the path, e.g. an account xpub kept by a watch-only wallet, go first.
+/// Version is not compared, so SLIP-132 keys ("zpub", "vpub", ...) match as well.
+pub fn rank_by_xpub(
+    candidates: Vec<Mnemonic>,
+    passphrase: &str,
+    path: &DerivationPath,
+    xpub: &str,
+) -> Result<Vec<Mnemonic>, WalletError> {
+    let known = extended_key_payload(xpub)?;
+
+    rank_by(candidates, |candidate| {
+        let key = Wallet::new(candidate, passphrase)?.derive(path)?;
+
+        Ok(extended_key_payload(&key.to_xpub(Network::Bitcoin))? == known)
+    })
+}
+
+/// Matching candidates first, both groups in their original order
+fn rank_by<F>(candidates: Vec<Mnemonic>, mut matches: F) -> Result<Vec<Mnemonic>, WalletError>
+where
+    F: FnMut(&Mnemonic) -> Result<bool, WalletError>,
+{
+    let mut matching = vec![];
+    let mut rest = vec![];
+
+    for candidate in candidates {
+        if matches(&candidate)? {
+            matching.push(candidate);
+        } else {
+            rest.push(candidate);
+        }
+    }
+
+    matching.append(&mut rest);
+
+    Ok(matching)
+}
+
+/// Serialized extended key without its 4 version bytes
+fn extended_key_payload(key: &str) -> Result<Vec<u8>, Bip32Error> {
+    let mut data = bs58::decode(key)
+        .with_check(None)
+        .into_vec()
+        .map_err(|_| Bip32Error::InvalidExtendedKey)?;
+    if data.len() != 78 {
+        return Err(Bip32Error::InvalidExtendedKey);
+    }
+
+    Ok(data.split_off(4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::{abandon_about, recover_phrase};
+
+    fn wallet() -> Wallet {
+        Wallet::new(&abandon_about(), "").unwrap()
+    }
+
+    #[test]
+    fn master_fingerprint_of_test_mnemonic() {
+        assert_eq!("73c5da0a", hex::encode(wallet().fingerprint()));
+    }
+
+    #[test]
+    fn cached_derivation_is_same_as_uncached() {
+        let mut wallet = wallet();
+        let account: DerivationPath = "m/84'/0'/0'/0".parse().unwrap();
+
+        for index in 0..100 {
+            let path = account.child(index);
+
+            assert_eq!(
+                wallet
+                    .master()
+                    .derive(&path)
+                    .unwrap()
+                    .to_xprv(Network::Bitcoin),
+                wallet.derive(&path).unwrap().to_xprv(Network::Bitcoin)
+            );
+        }
+        // Only the common parent is cached
+        assert_eq!(1, wallet.cache.borrow().len());
+
+        // Account key has a different parent, it is cached next to the first one
+        let path: DerivationPath = "m/84'/0'/0'".parse().unwrap();
+        assert_eq!(
+            wallet
+                .master()
+                .derive(&path)
+                .unwrap()
+                .to_xprv(Network::Bitcoin),
+            wallet.derive(&path).unwrap().to_xprv(Network::Bitcoin)
+        );
+        assert_eq!(2, wallet.cache.borrow().len());
+
+        wallet.clear_cache();
+        assert!(wallet.cache.borrow().is_empty());
+    }
+
+    #[test]
+    fn cache_keeps_recently_used_nodes() {
+        let wallet = wallet();
+
+        for account in 0..CACHE_SIZE as u32 + 4 {
+            wallet
+                .receive_address(AddressType::NativeSegwit, Network::Bitcoin, 0)
+                .unwrap();
+            wallet
+                .derive(&format!("m/44'/0'/{}'/0/0", account).parse().unwrap())
+                .unwrap();
+        }
+
+        let cache = wallet.cache.borrow();
+        assert_eq!(CACHE_SIZE, cache.len());
+        // Receive addresses of the first account share the parent, it is used all the time
+        assert!(cache
+            .iter()
+            .any(|(prefix, _)| prefix
+                == "m/84'/0'/0'/0".parse::<DerivationPath>().unwrap().indices()));
+        assert!(!cache
+            .iter()
+            .any(|(prefix, _)| prefix
+                == "m/44'/0'/0'/0".parse::<DerivationPath>().unwrap().indices()));
+    }
+
+    #[test]
+    fn derive_many_derives_shared_prefix_once() {
+        let wallet = wallet();
+        let paths: Vec<DerivationPath> = ["m/0'", "m/0'/0", "m/0'/1", "m/0'/0"]
+            .iter()
+            .map(|path| path.parse().unwrap())
+            .collect();
+
+        let (keys, derivations) = wallet.derive_shared(&paths).unwrap();
+
+        assert_eq!(3, derivations);
+        assert_eq!(
+            vec!["m/0'", "m/0'/0", "m/0'/1"],
+            keys.keys().collect::<Vec<_>>()
+        );
+        for path in &paths {
+            assert_eq!(
+                wallet.derive(path).unwrap().to_xprv(Network::Bitcoin),
+                keys[&path.to_string()].to_xprv(Network::Bitcoin)
+            );
+        }
+    }
+
+    #[test]
+    fn derive_many_of_master_and_no_paths() {
+        let wallet = wallet();
+
+        assert!(wallet.derive_many(&[]).unwrap().is_empty());
+
+        let keys = wallet.derive_many(&["m".parse().unwrap()]).unwrap();
+        assert_eq!(
+            wallet.master().to_xprv(Network::Bitcoin),
+            keys["m"].to_xprv(Network::Bitcoin)
+        );
+    }
+
+    #[test]
+    fn hidden_wallets_differ_by_passphrase() {
+        let mnemonic = abandon_about();
+
+        let wallets = mnemonic.hidden_wallets(&["", "TREZOR"]).unwrap();
+
+        assert_eq!(2, wallets.len());
+        assert_eq!(wallet().fingerprint(), wallets[0].fingerprint());
+        assert_ne!(wallets[0].fingerprint(), wallets[1].fingerprint());
+        assert_eq!(
+            "xprv9s21ZrQH143K3h3fDYiay8mocZ3afhfULfb5GX8kCBdno77K4HiA15Tg23wpbeF1pLfs1c5SPmYHrEpTuuRhxMwvKDwqdKiGJS9XFKzUsAF",
+            wallets[1].master().to_xprv(Network::Bitcoin)
+        );
+    }
+
+    #[test]
+    fn scan_addresses_of_native_segwit_account() {
+        let wallet = wallet();
+        let account: DerivationPath = "m/84'/0'/0'/0".parse().unwrap();
+
+        let addresses = wallet.scan_addresses(&account, 20).unwrap();
+
+        assert_eq!(20, addresses.len());
+        assert_eq!("bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu", addresses[0]);
+        assert_eq!("bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g", addresses[1]);
+
+        for (index, address) in addresses.iter().enumerate() {
+            let key = wallet.derive(&account.child(index as u32)).unwrap();
+
+            assert_eq!(
+                &key.address(AddressType::NativeSegwit, Network::Bitcoin)
+                    .unwrap(),
+                address
+            );
+        }
+
+        let mut unique = addresses.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(addresses.len(), unique.len());
+    }
+
+    #[test]
+    fn scan_addresses_infers_type_and_network() {
+        let wallet = wallet();
+
+        let legacy = wallet
+            .scan_addresses(&"m/44'/0'/0'/0".parse().unwrap(), 1)
+            .unwrap();
+        assert_eq!(vec!["1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA"], legacy);
+
+        let testnet = wallet
+            .scan_addresses(&"m/84'/1'/0'/0".parse().unwrap(), 3)
+            .unwrap();
+        assert!(testnet.iter().all(|address| address.starts_with("tb1q")));
+    }
+
+    #[test]
+    fn receive_address_of_first_account() {
+        let wallet = wallet();
+
+        assert_eq!(
+            "bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g",
+            wallet
+                .receive_address(AddressType::NativeSegwit, Network::Bitcoin, 1)
+                .unwrap()
+        );
+        assert_eq!(
+            "37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf",
+            wallet
+                .receive_address(AddressType::P2shSegwit, Network::Bitcoin, 0)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn descriptor_of_native_segwit_account() {
+        assert_eq!(
+            "wpkh([73c5da0a/84h/0h/0h]xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V/0/*)",
+            wallet()
+                .descriptor(AddressType::NativeSegwit, 0, Network::Bitcoin)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn descriptor_wraps_script_of_address_type() {
+        let wallet = wallet();
+
+        let legacy = wallet
+            .descriptor(AddressType::Legacy, 1, Network::Bitcoin)
+            .unwrap();
+        assert!(legacy.starts_with("pkh([73c5da0a/44h/0h/1h]xpub"));
+        assert!(legacy.ends_with("/0/*)"));
+
+        let nested = wallet
+            .descriptor(AddressType::P2shSegwit, 0, Network::Testnet)
+            .unwrap();
+        assert!(nested.starts_with("sh(wpkh([73c5da0a/49h/1h/0h]tpub"));
+        assert!(nested.ends_with("/0/*))"));
+    }
+
+    #[test]
+    fn recovery_sheet_of_test_mnemonic() {
+        let mnemonic = abandon_about();
+
+        let sheet = wallet()
+            .recovery_sheet(&mnemonic, "", AddressType::NativeSegwit, Network::Bitcoin)
+            .unwrap();
+
+        assert!(sheet.starts_with(" 1. abandon\n 2. abandon\n"));
+        assert!(sheet.contains("\n12. about\n"));
+        assert!(sheet.contains("Master fingerprint: 73c5da0a\n"));
+        assert!(sheet.contains("Derivation path: m/84'/0'/0'/0/0\n"));
+        assert!(
+            sheet.contains("First receive address: bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu\n")
+        );
+    }
+
+    #[test]
+    fn recovery_sheet_rejects_mnemonic_of_other_wallet() {
+        let mnemonic = abandon_about();
+
+        assert_eq!(
+            Err(WalletError::Bip32(Bip32Error::FingerprintMismatch {
+                expected: "73c5da0a".to_string(),
+                found: hex::encode(Wallet::new(&mnemonic, "TREZOR").unwrap().fingerprint()),
+            })),
+            wallet().recovery_sheet(
+                &mnemonic,
+                "TREZOR",
+                AddressType::NativeSegwit,
+                Network::Bitcoin
+            )
+        );
+    }
+
+    #[test]
+    fn mnemonic_controls_address_at_its_path() {
+        let mnemonic = abandon_about();
+        let controls = |passphrase, path: &str, address| {
+            mnemonic_controls_address(
+                &mnemonic,
+                passphrase,
+                &path.parse().unwrap(),
+                address,
+                Network::Bitcoin,
+            )
+            .unwrap()
+        };
+
+        assert!(controls(
+            "",
+            "m/84'/0'/0'/0/0",
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+        ));
+        assert!(controls(
+            "",
+            "m/44'/0'/0'/0/0",
+            "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA"
+        ));
+        assert!(!controls(
+            "",
+            "m/84'/0'/0'/0/1",
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+        ));
+        assert!(!controls(
+            "TREZOR",
+            "m/84'/0'/0'/0/0",
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu"
+        ));
+    }
+
+    #[cfg(feature = "taproot")]
+    #[test]
+    fn mnemonic_controls_taproot_address() {
+        assert!(mnemonic_controls_address(
+            &abandon_about(),
+            "",
+            &"m/86'/0'/0'/0/0".parse().unwrap(),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            Network::Bitcoin,
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn mnemonic_controls_address_rejects_unknown_purpose() {
+        assert!(matches!(
+            mnemonic_controls_address(
+                &abandon_about(),
+                "",
+                &"m/0/0".parse().unwrap(),
+                "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA",
+                Network::Bitcoin,
+            ),
+            Err(WalletError::Bip32(Bip32Error::InvalidAddressType(_)))
+        ));
+    }
+
+    /// Few first candidates for the missing last word, reversed so the matching one is not
+    /// already first. Deriving all 128 of them takes a while.
+    fn candidates() -> Vec<Mnemonic> {
+        let mut candidates = recover_phrase(&format!("{}?", "abandon ".repeat(11))).unwrap();
+        candidates.truncate(8);
+        candidates.reverse();
+
+        candidates
+    }
+
+    #[test]
+    fn rank_by_address_puts_matching_first() {
+        let candidates = candidates();
+        assert_ne!(abandon_about(), candidates[0]);
+
+        let ranked = rank_by_address(
+            candidates.clone(),
+            "",
+            &"m/84'/0'/0'/0/0".parse().unwrap(),
+            "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu",
+            Network::Bitcoin,
+        )
+        .unwrap();
+
+        assert_eq!(candidates.len(), ranked.len());
+        assert_eq!(abandon_about(), ranked[0]);
+        assert_ne!(abandon_about(), ranked[1]);
+    }
+
+    #[test]
+    fn rank_by_xpub_accepts_slip132_keys() {
+        let path: DerivationPath = "m/84'/0'/0'".parse().unwrap();
+
+        for xpub in &[
+            "xpub6CatWdiZiodmUeTDp8LT5or8nmbKNcuyvz7WyksVFkKB4RHwCD3XyuvPEbvqAQY3rAPshWcMLoP2fMFMKHPJ4ZeZXYVUhLv1VMrjPC7PW6V",
+            "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs",
+        ] {
+            let ranked = rank_by_xpub(candidates(), "", &path, xpub).unwrap();
+
+            assert_eq!(abandon_about(), ranked[0]);
+        }
+
+        assert_eq!(
+            Err(WalletError::Bip32(Bip32Error::InvalidExtendedKey)),
+            rank_by_xpub(candidates(), "", &path, "xpub123")
+        );
+    }
+
+    #[test]
+    fn key_origin_of_native_segwit_account() {
+        assert_eq!(
+            "[73c5da0a/84'/0'/0']zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs",
+            wallet()
+                .key_origin(&"m/84'/0'/0'".parse().unwrap(), Network::Bitcoin)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn key_origin_of_multisig_accounts() {
+        let wallet = wallet();
+        let path = "m/48'/0'/0'/2'".parse().unwrap();
+
+        let origin = wallet.key_origin(&path, Network::Bitcoin).unwrap();
+
+        let (prefix, key) = origin.split_at(origin.find(']').unwrap() + 1);
+        assert_eq!("[73c5da0a/48'/0'/0'/2']", prefix);
+        assert!(key.starts_with("Zpub"));
+        // Only the version differs from the plain xpub of the key
+        let xpub = wallet.derive(&path).unwrap().to_xpub(Network::Bitcoin);
+        assert_eq!(
+            bs58::decode(xpub).with_check(None).into_vec().unwrap()[4..],
+            bs58::decode(key).with_check(None).into_vec().unwrap()[4..]
+        );
+
+        for (path, network, prefix) in &[
+            ("m/48'/0'/0'/1'", Network::Bitcoin, "Ypub"),
+            ("m/48'/1'/0'/1'", Network::Testnet, "Upub"),
+            ("m/48'/1'/0'/2'", Network::Testnet, "Vpub"),
+            ("m/48'/0'/0'", Network::Bitcoin, "xpub"),
+        ] {
+            let origin = wallet.key_origin(&path.parse().unwrap(), *network).unwrap();
+            assert!(origin.contains(&format!("]{}", prefix)), "{}", origin);
+        }
+    }
+}
@@ -2,36 +2,114 @@
 //! # Resources
 //! https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki
 //! https://iancoleman.io/bip39/#english
+use super::{split_phrase, word_index, Language, Mnemonic, Passphrase, Wordlist};
 use crate::entropy::EntropySource;
+use crate::redacted::Redacted;
 use hmac::Hmac;
 use log::{debug, error, info};
 use pbkdf2::pbkdf2;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256, Sha512};
+use std::borrow::Borrow;
 use std::convert::TryFrom;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::io::BufRead;
+use std::time::{Duration, Instant};
 use std::vec::Vec;
+use unicode_normalization::UnicodeNormalization;
 // TODO: no setup/teardown for tests, shame!
 use test_env_log::test;
 use thiserror::Error;
 use to_binary::BinaryString;
-pub type Mnemonics = Vec<String>;
+use zeroize::Zeroizing;
 pub type Seed = Vec<u8>;
+
+/// PBKDF2 iterations of the seed derivation, fixed by BIP39
+pub(super) const PBKDF2_ROUNDS: u32 = 2048;
+
+/// Number of words in the BIP39 dictionary
+pub const WORDLIST_SIZE: usize = 2048;
+/// Each word encodes that many bits, 2^BITS_PER_WORD == WORDLIST_SIZE
+pub const BITS_PER_WORD: usize = 11;
 use std::fmt::Write;
 
-#[derive(Error, Debug, PartialEq)]
+/// Serialized with variant name in `error`, and its fields, if any, in `details`.
+/// Variant names are stable, e.g. for programs parsing the CLI `--json` output.
+#[derive(Error, Debug, PartialEq, Serialize)]
+#[serde(tag = "error", content = "details")]
 pub enum Bip39Error {
     #[error("Invalid argument to convert WordsCount {}", .0)]
     InvalidWordsCount(usize),
 
     #[error("Index for word {} not found!", .0)]
     InvalidWord(String),
+
+    #[error("Failed to get entropy: {}", .0)]
+    EntropyUnavailable(String),
+
+    #[error("Invalid wordlist, line {}: {}", .line, .reason)]
+    InvalidWordlist { line: usize, reason: String },
+
+    #[error("Invalid wordlist, expected {} words, got {}", .expected, .found)]
+    InvalidWordlistLength { expected: usize, found: usize },
+
+    #[error("Too many unknown words {}, at most 2 can be recovered", .0)]
+    TooManyUnknownWords(usize),
+
+    #[error("Checksum nibble has to be 0 - 15, got {}", .0)]
+    InvalidNibble(u8),
+
+    #[error("Entropy rejected, {}", .0)]
+    LowQualityEntropy(String),
+
+    #[error("Invalid backup: {}", .0)]
+    InvalidBackup(String),
+
+    #[error("Decryption failed, wrong password or corrupted backup")]
+    DecryptionFailed,
+
+    #[error("Invalid entropy: {}", .0)]
+    InvalidEntropy(String),
+
+    #[error("2^{} combinations do not fit in u64", .0)]
+    TooManyCombinations(usize),
+
+    #[error("Word index {} out of the dictionary", .0)]
+    InvalidWordIndex(usize),
+
+    #[error("Mnemonics not in canonical form: {}", .0)]
+    NonCanonical(String),
+
+    #[error("Invalid checksum")]
+    InvalidChecksum,
+
+    #[error("Language {:?} is not enabled, compile with its cargo feature", .0)]
+    LanguageNotEnabled(Language),
+
+    #[error("Self test failed: {}", .0)]
+    SelfTestFailed(String),
+
+    #[error("Entropy not gathered within {:?}", .0)]
+    EntropyTimeout(Duration),
+
+    #[error("Shares have to be of the same length, got {} and {} bytes", .0, .1)]
+    ShareLengthMismatch(usize, usize),
+
+    #[error("Internal error: {}", .0)]
+    Internal(String),
+
+    #[error("No matching mnemonics found in {} tries", .0)]
+    TriesExhausted(usize),
+
+    #[error(
+        "Mnemonics cannot be recovered from the seed, \
+         PBKDF2 used to derive the seed is a one-way function"
+    )]
+    Irreversible,
 }
 
 // TODO: any better alternative for narrowing type to have only a subset of valid integer values?
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum WordsCount {
     _12 = 12,
     _15 = 15,
@@ -40,6 +118,31 @@ pub enum WordsCount {
     _24 = 24,
 }
 
+impl WordsCount {
+    /// All valid words counts, ascending, e.g. for UI dropdowns
+    pub fn all() -> impl Iterator<Item = WordsCount> {
+        [
+            WordsCount::_12,
+            WordsCount::_15,
+            WordsCount::_18,
+            WordsCount::_21,
+            WordsCount::_24,
+        ]
+        .iter()
+        .copied()
+    }
+
+    /// Same as `all`, as plain numbers
+    pub fn values() -> [usize; 5] {
+        [12, 15, 18, 21, 24]
+    }
+
+    /// Number of entropy bits encoded by given amount of words, 128 - 256
+    pub fn keyspace_bits(&self) -> usize {
+        *self as usize / 3 * 32
+    }
+}
+
 /// Converting usize to WordsCount
 impl TryFrom<usize> for WordsCount {
     type Error = Bip39Error;
@@ -56,6 +159,130 @@ impl TryFrom<usize> for WordsCount {
     }
 }
 
+/// Describes how many different wallets can be generated with given amount of words,
+/// e.g. "2^128 possible 12-word wallets"
+pub fn keyspace_description(word_count: WordsCount) -> String {
+    format!(
+        "2^{} possible {}-word wallets",
+        word_count.keyspace_bits(),
+        word_count as usize
+    )
+}
+
+/// Returns how many of all dictionary words are valid as the last word: `(valid, total)`,
+/// e.g. `(128, 2048)` for 12 words.
+///
+/// Last word carries the checksum, so only 1 in 2^checksum_bits words passes the validation.
+pub fn checksum_strength(word_count: WordsCount) -> (usize, usize) {
+    let total = WORDLIST_SIZE;
+    let checksum_len = word_count.keyspace_bits() / 32;
+
+    (total >> checksum_len, total)
+}
+
+/// Approximate entropy, in bits, left after the first `fixed_words` words are chosen,
+/// e.g. for a strength meter updated while words are picked by hand.
+///
+/// Every word carries 11 bits, but the last one is constrained by the checksum,
+/// so it only adds entropy bits, e.g. 7 for 12 words (128 valid last words).
+pub fn remaining_entropy_bits(fixed_words: usize, total: WordsCount) -> f64 {
+    let fixed_bits = fixed_words.saturating_mul(BITS_PER_WORD) as f64;
+
+    (total.keyspace_bits() as f64 - fixed_bits).max(0.0)
+}
+
+/// Describes mnemonics the way steel seed plates are stamped: for every word its position
+/// in the phrase, first 4 letters (enough to identify any word), and position in the dictionary.
+/// Both positions are counted from 1, as on the plates, e.g. `(1, "stic", 1710)`.
+pub fn to_plate_format(mnemonics: &[String]) -> Result<Vec<(usize, String, u16)>, Bip39Error> {
+    mnemonics
+        .iter()
+        .enumerate()
+        .map(|(position, word)| {
+            let index = word_index(word, Language::English)
+                .ok_or_else(|| Bip39Error::InvalidWord(word.clone()))?;
+
+            Ok((position + 1, word.chars().take(4).collect(), index + 1))
+        })
+        .collect()
+}
+
+/// Returns how many valid mnemonics of `total` words start with `prefix_words`,
+/// i.e. keyspace left to brute-force once these words are known.
+///
+/// Every free word gives 11 bits, minus checksum bits, which are determined by the entropy.
+/// For all words fixed it is 1 or 0, depending on the checksum.
+pub fn mnemonics_with_prefix_count(
+    prefix_words: &[String],
+    total: WordsCount,
+) -> Result<u64, Bip39Error> {
+    let total_words = total as usize;
+
+    if prefix_words.len() > total_words {
+        return Err(Bip39Error::InvalidWordsCount(prefix_words.len()));
+    }
+
+    let words = get_dictionary();
+    if let Some(word) = prefix_words.iter().find(|word| !words.contains(word)) {
+        return Err(Bip39Error::InvalidWord(word.clone()));
+    }
+
+    if prefix_words.len() == total_words {
+        return Ok(is_checksum_valid(prefix_words)? as u64);
+    }
+
+    let checksum_len = total.keyspace_bits() / 32;
+    let free_bits = (total_words - prefix_words.len()) * BITS_PER_WORD - checksum_len;
+
+    if free_bits >= 64 {
+        return Err(Bip39Error::TooManyCombinations(free_bits));
+    }
+
+    Ok(1 << free_bits)
+}
+
+/// Returns how many completions of `prefix_bits` (leading bits of entropy followed by
+/// checksum, as '0' and '1' characters) to the full `total` length pass the checksum.
+///
+/// Checksum is determined by the entropy, so it filters out all but 1 in 2^checksum_bits
+/// of the bit strings: every entropy completion has exactly one valid checksum.
+/// Once checksum bits are in the prefix, it is 1 or 0.
+pub fn valid_entropy_completions(prefix_bits: &str, total: WordsCount) -> Result<u64, Bip39Error> {
+    let entropy_len = total.keyspace_bits();
+    let checksum_len = entropy_len / 32;
+
+    if prefix_bits.chars().any(|bit| bit != '0' && bit != '1') {
+        return Err(Bip39Error::InvalidEntropy(
+            "prefix has to consist of 0 and 1 only".to_string(),
+        ));
+    }
+    if prefix_bits.len() > entropy_len + checksum_len {
+        return Err(Bip39Error::InvalidEntropy(format!(
+            "prefix has {} bits, {} words carry {}",
+            prefix_bits.len(),
+            total as usize,
+            entropy_len + checksum_len
+        )));
+    }
+
+    if prefix_bits.len() <= entropy_len {
+        let free_bits = entropy_len - prefix_bits.len();
+
+        if free_bits >= 64 {
+            return Err(Bip39Error::TooManyCombinations(free_bits));
+        }
+
+        return Ok(1 << free_bits);
+    }
+
+    let (entropy_bits, checksum_bits) = prefix_bits.split_at(entropy_len);
+    let entropy = hex::decode(bitstring_to_hex(entropy_bits))
+        .map_err(|e| Bip39Error::Internal(e.to_string()))?;
+    let checksum = format!("{:08b}", Sha256::digest(&entropy)[0]);
+
+    Ok(checksum.starts_with(checksum_bits) as u64)
+}
+
 /// Gets string representing binary number of arbitrary size
 /// Returns it's hexadecimal representation
 fn bitstring_to_hex(bitstring: &str) -> String {
@@ -85,24 +312,30 @@ fn bitstring_to_hex(bitstring: &str) -> String {
     hex
 }
 
-/// Opens a file containing dictionary of words used in mnemonic generation
+/// Dictionary of words used in mnemonic generation, embedded in the binary
 fn get_dictionary() -> Vec<String> {
-    let mut filename = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    filename.push("src/bip39/english.txt");
-    let reader = BufReader::new(File::open(filename).unwrap());
-
-    let words: Vec<_> = reader.lines().map(|word| word.unwrap()).collect();
-    words
+    Wordlist::english().iter().map(String::from).collect()
 }
 
-/// Gets mnemonics collection, calculates their checksum and returns bool indicating if it is correct.
-pub fn is_checksum_valid(mnemonics: &[String]) -> Result<bool, Bip39Error> {
-    let words = get_dictionary();
-
-    let word_count = mnemonics.len();
-    let entropy_len = word_count / 3 * 32;
-    let checksum_len = entropy_len / 32;
+/// Reads dictionary, one word per line. Empty lines (e.g. trailing newline) are skipped,
+/// otherwise every following index would be shifted.
+/// Returns error with the line number (counted from 1) if line cannot be read, e.g. is not UTF-8.
+pub fn read_wordlist<R: BufRead>(reader: R) -> Result<Vec<String>, Bip39Error> {
+    reader
+        .lines()
+        .enumerate()
+        .map(|(i, word)| {
+            word.map_err(|e| Bip39Error::InvalidWordlist {
+                line: i + 1,
+                reason: e.to_string(),
+            })
+        })
+        .filter(|word| !matches!(word, Ok(word) if word.is_empty()))
+        .collect()
+}
 
+/// Converts mnemonics to indices in the dictionary, and concatenates them to the bitstring
+fn mnemonics_to_bitstring(mnemonics: &[String], words: &[String]) -> Result<String, Bip39Error> {
     // Convert words to indices
     // Change indices to bitstring
     let mut bitstring = String::default();
@@ -114,15 +347,90 @@ pub fn is_checksum_valid(mnemonics: &[String]) -> Result<bool, Bip39Error> {
             .ok_or_else(|| Bip39Error::InvalidWord(memo.clone()))? as u32;
 
         // Convert to bit, 11 bits wide, leading zeros
-        write!(bitstring, "{:011b}", position).unwrap();
+        write!(bitstring, "{:0width$b}", position, width = BITS_PER_WORD).unwrap();
+    }
+
+    debug!("Bitstring is {}", Redacted::new(&bitstring));
+
+    Ok(bitstring)
+}
+
+/// Outcome of the mnemonics validation, everything UI needs to tell the user what is wrong
+#[derive(Debug, PartialEq)]
+pub enum ChecksumStatus {
+    Valid,
+    /// Checksum bits calculated from the entropy, and the ones found in the last word
+    Invalid {
+        expected: u8,
+        got: u8,
+    },
+    WrongLength(usize),
+    UnknownWord(String),
+    /// Validation failed for other reason, e.g. internal one
+    Error(Bip39Error),
+}
+
+/// Gets mnemonics collection, calculates their checksum and returns bool indicating if it is correct.
+/// Thin wrapper over `checksum_status`, wrong length and unknown word are reported as errors.
+pub fn is_checksum_valid(mnemonics: &[String]) -> Result<bool, Bip39Error> {
+    match checksum_status(mnemonics) {
+        ChecksumStatus::Valid => Ok(true),
+        ChecksumStatus::Invalid { .. } => Ok(false),
+        ChecksumStatus::WrongLength(len) => Err(Bip39Error::InvalidWordsCount(len)),
+        ChecksumStatus::UnknownWord(word) => Err(Bip39Error::InvalidWord(word)),
+        ChecksumStatus::Error(e) => Err(e),
     }
+}
+
+/// Same as `is_checksum_valid`, with the dictionary of the given language.
+/// Words are NFKD normalized before the lookup, so e.g. accented letters typed as
+/// single characters are found.
+pub fn is_checksum_valid_in(mnemonics: &[String], lang: Language) -> Result<bool, Bip39Error> {
+    is_checksum_valid_with(mnemonics, Wordlist::for_language(lang)?)
+}
+
+/// Same as `is_checksum_valid_in`, with any dictionary, e.g. `Wordlist::custom`
+pub fn is_checksum_valid_with(
+    mnemonics: &[String],
+    wordlist: &Wordlist,
+) -> Result<bool, Bip39Error> {
+    WordsCount::try_from(mnemonics.len())?;
+
+    let indices = mnemonics
+        .iter()
+        .map(|word| {
+            wordlist
+                .index_of(&word.nfkd().collect::<String>())
+                .map(usize::from)
+                .ok_or_else(|| Bip39Error::InvalidWord(word.clone()))
+        })
+        .collect::<Result<Vec<usize>, Bip39Error>>()?;
+
+    Ok(is_indices_checksum_valid(&indices))
+}
+
+/// Gets mnemonics collection, calculates their checksum and compares with the one
+/// encoded in the last word.
+pub fn checksum_status(mnemonics: &[String]) -> ChecksumStatus {
+    let words = get_dictionary();
+
+    let word_count = match WordsCount::try_from(mnemonics.len()) {
+        Ok(word_count) => word_count,
+        Err(_) => return ChecksumStatus::WrongLength(mnemonics.len()),
+    };
+    let entropy_len = word_count.keyspace_bits();
+    let checksum_len = entropy_len / 32;
 
-    debug!("Bitstring is {}", bitstring);
+    let bitstring = match mnemonics_to_bitstring(mnemonics, &words) {
+        Ok(bitstring) => bitstring,
+        Err(Bip39Error::InvalidWord(word)) => return ChecksumStatus::UnknownWord(word),
+        Err(e) => return ChecksumStatus::Error(e),
+    };
 
     let entropy_hex = bitstring_to_hex(&bitstring[..entropy_len]);
     let checksum_memo = &bitstring[entropy_len..];
 
-    debug!("Entropy: {}", entropy_hex);
+    debug!("Entropy: {}", Redacted::new(&entropy_hex));
 
     // hex_string -> hex::decode [u8] -> Sha256::digest GenericArray -> .as_slice() &[u8] -> .0 bin string
     let checksum =
@@ -131,49 +439,498 @@ pub fn is_checksum_valid(mnemonics: &[String]) -> Result<bool, Bip39Error> {
 
     if checksum_memo == checksum {
         info!("Checksum is correct!");
-        Ok(true)
+        ChecksumStatus::Valid
     } else {
         error!(
             "Incorrect checksum expected {}, calculated {}",
-            checksum_memo, checksum
+            Redacted::new(checksum_memo),
+            Redacted::new(checksum)
         );
-        Ok(false)
+        ChecksumStatus::Invalid {
+            expected: u8::from_str_radix(checksum, 2).unwrap(),
+            got: u8::from_str_radix(checksum_memo, 2).unwrap(),
+        }
+    }
+}
+
+/// Mnemonics split to the parts defined by BIP39
+#[derive(Debug, PartialEq)]
+pub struct Decomposition {
+    /// Position of every word in the dictionary
+    pub indices: Vec<u16>,
+    pub entropy_bytes: Vec<u8>,
+    /// Checksum bits, MSB first, as encoded in the last word
+    pub checksum_bits: Vec<bool>,
+}
+
+/// Splits mnemonics to word indices, entropy and checksum bits, checksum is not verified.
+/// Useful for debugging, or to compare with other implementations.
+pub fn decompose(mnemonics: &[String]) -> Result<Decomposition, Bip39Error> {
+    let word_count = WordsCount::try_from(mnemonics.len())?;
+    let words = get_dictionary();
+
+    let indices = mnemonics
+        .iter()
+        .map(|memo| {
+            words
+                .iter()
+                .position(|el| memo == el)
+                .ok_or_else(|| Bip39Error::InvalidWord(memo.clone()))
+        })
+        .collect::<Result<Vec<usize>, Bip39Error>>()?;
+
+    let (entropy_bytes, checksum) = indices_to_entropy(&indices);
+    let checksum_len = word_count.keyspace_bits() / 32;
+
+    Ok(Decomposition {
+        indices: indices.iter().map(|&index| index as u16).collect(),
+        entropy_bytes,
+        checksum_bits: (0..checksum_len)
+            .rev()
+            .map(|i| (checksum >> i) & 1 == 1)
+            .collect(),
+    })
+}
+
+/// Returns the entropy mnemonics were generated from, reverse of `generate_mnemonics`.
+/// Fails if the checksum does not match, such mnemonics were not generated from any entropy.
+/// Checksum bits, without the verification, are given by `decompose`.
+pub fn mnemonic_to_entropy(mnemonics: &[String]) -> Result<Vec<u8>, Bip39Error> {
+    if !is_checksum_valid(mnemonics)? {
+        return Err(Bip39Error::InvalidChecksum);
+    }
+
+    Ok(decompose(mnemonics)?.entropy_bytes)
+}
+
+/// Packs 11 bit word indices to bytes of entropy, returns them together with checksum bits
+/// that follow the entropy
+pub(super) fn indices_to_entropy(indices: &[usize]) -> (Vec<u8>, u8) {
+    let entropy_bytes = indices.len() / 3 * 32 / 8;
+    let mut entropy = Vec::with_capacity(entropy_bytes);
+
+    // Bits not yet moved to entropy, at most 11 + 7 of them
+    let mut acc: u32 = 0;
+    let mut acc_len = 0;
+
+    for index in indices {
+        acc = (acc << BITS_PER_WORD) | *index as u32;
+        acc_len += BITS_PER_WORD;
+
+        while acc_len >= 8 && entropy.len() < entropy_bytes {
+            acc_len -= 8;
+            entropy.push((acc >> acc_len) as u8);
+            acc &= (1 << acc_len) - 1;
+        }
+    }
+
+    // What is left is the checksum
+    (entropy, acc as u8)
+}
+
+/// Same as `is_checksum_valid`, but works on indices, no strings involved, so is fast enough
+/// for brute-force searches
+pub(super) fn is_indices_checksum_valid(indices: &[usize]) -> bool {
+    let checksum_len = indices.len() / 3;
+    let (entropy, checksum) = indices_to_entropy(indices);
+
+    Sha256::digest(&entropy)[0] >> (8 - checksum_len) == checksum
+}
+
+/// Lists every last word giving a valid checksum for the given first words,
+/// e.g. for mnemonics made by hand with dice, where the last word has to be calculated.
+/// There are 2^(11 - checksum_bits) of them, 128 for 12 words, 8 for 24.
+pub fn valid_final_words(first_words: &[String]) -> Result<Vec<&'static str>, Bip39Error> {
+    WordsCount::try_from(first_words.len() + 1)
+        .map_err(|_| Bip39Error::InvalidWordsCount(first_words.len()))?;
+
+    let mut indices = first_words
+        .iter()
+        .map(|word| {
+            word_index(word, Language::English)
+                .map(usize::from)
+                .ok_or_else(|| Bip39Error::InvalidWord(word.clone()))
+        })
+        .collect::<Result<Vec<usize>, Bip39Error>>()?;
+    indices.push(0);
+    let last = indices.len() - 1;
+
+    Ok(Language::English
+        .words()
+        .enumerate()
+        .filter_map(|(index, word)| {
+            indices[last] = index;
+            if is_indices_checksum_valid(&indices) {
+                Some(word)
+            } else {
+                None
+            }
+        })
+        .collect())
+}
+
+/// Recovers mnemonics with some words missing (`None`), for example from a damaged backup.
+///
+/// Tries all 2048 words on each missing position, returns every combination
+/// with a valid checksum. At most 2 missing words are supported, it is 2048^2 tries already.
+/// Usually many mnemonics are returned, 1 in 2^checksum_bits of the candidates passes
+/// the checksum, so the right one has to be picked by other means, e.g. known address.
+pub fn recover_missing_words(
+    words: &[Option<String>],
+    total: WordsCount,
+) -> Result<Vec<Mnemonic>, Bip39Error> {
+    recover_missing_indices(words, total, Wordlist::english())?
+        .into_iter()
+        .map(|indices| Mnemonic::from_indices(indices, Language::English))
+        .collect()
+}
+
+/// Same as `recover_missing_words`, with any dictionary, e.g. `Wordlist::custom`
+pub fn recover_missing_words_with(
+    words: &[Option<String>],
+    total: WordsCount,
+    wordlist: &Wordlist,
+) -> Result<Vec<Vec<String>>, Bip39Error> {
+    Ok(recover_missing_indices(words, total, wordlist)?
+        .iter()
+        .map(|indices| indices_to_words_in(indices, wordlist))
+        .collect())
+}
+
+/// Indices of every `recover_missing_words` candidate
+fn recover_missing_indices(
+    words: &[Option<String>],
+    total: WordsCount,
+    wordlist: &Wordlist,
+) -> Result<Vec<Vec<usize>>, Bip39Error> {
+    if words.len() != total as usize {
+        return Err(Bip39Error::InvalidWordsCount(words.len()));
+    }
+
+    let mut indices = vec![0; words.len()];
+    let mut missing = vec![];
+
+    for (position, word) in words.iter().enumerate() {
+        match word {
+            Some(word) => {
+                indices[position] = wordlist
+                    .index_of(word)
+                    .ok_or_else(|| Bip39Error::InvalidWord(word.clone()))?
+                    as usize
+            }
+            None => missing.push(position),
+        }
+    }
+
+    if missing.len() > 2 {
+        return Err(Bip39Error::TooManyUnknownWords(missing.len()));
+    }
+
+    let mut found = vec![];
+
+    // Treat missing words as digits of a base 2048 number, and count through all of them
+    for candidate in 0..WORDLIST_SIZE.pow(missing.len() as u32) {
+        for (i, position) in missing.iter().enumerate() {
+            indices[*position] = candidate / WORDLIST_SIZE.pow(i as u32) % WORDLIST_SIZE;
+        }
+
+        if is_indices_checksum_valid(&indices) {
+            found.push(indices.clone());
+        }
+    }
+
+    Ok(found)
+}
+
+/// Checks if mnemonics look like one of the well-known test vectors, like
+/// "abandon abandon ... about".
+///
+/// Those are published all over the internet, so funds sent to such a wallet can be
+/// swept by anyone. Mnemonics are flagged if all words are the same, or if the entropy
+/// they encode is a single byte repeated (0x00.., 0x7f.., 0x80.., 0xff..).
+/// Words outside of the dictionary are not flagged, use `is_checksum_valid` for that.
+pub fn looks_like_test_vector(mnemonics: &[String]) -> bool {
+    if mnemonics.is_empty() {
+        return false;
+    }
+
+    if mnemonics.iter().all(|memo| memo == &mnemonics[0]) {
+        return true;
+    }
+
+    let words = get_dictionary();
+    let entropy_len = mnemonics.len() / 3 * 32;
+
+    if entropy_len == 0 {
+        return false;
+    }
+
+    let bitstring = match mnemonics_to_bitstring(mnemonics, &words) {
+        Ok(bitstring) => bitstring,
+        Err(_) => return false,
+    };
+
+    // Split entropy to bytes, and compare each one with the first one
+    let entropy = &bitstring.as_bytes()[..entropy_len];
+    entropy.chunks(8).all(|byte| byte == &entropy[..8])
+}
+
+/// Calculates SHA256 of the canonical mnemonic sentence: NFKD, lower case, words joined
+/// with a single space, as given by `canonicalize`. So the same words typed differently,
+/// e.g. "Abandon" or with different Unicode normalization, give the same commitment.
+///
+/// Two parties who generated mnemonics independently can exchange commitments over
+/// an insecure channel, and compare them with `commitments_match`, without revealing the words.
+pub fn mnemonic_commitment(mnemonics: &[String]) -> [u8; 32] {
+    let sentence = Zeroizing::new(split_phrase(&mnemonics.join(" ")).join(" "));
+
+    let mut commitment = [0; 32];
+    commitment.copy_from_slice(&Sha256::digest(sentence.as_bytes()));
+
+    commitment
+}
+
+/// Compares two commitments in constant time, so timing does not leak
+/// how many leading bytes are equal.
+pub fn commitments_match(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    // Accumulate differences of all bytes, do not return early
+    let diff = a.iter().zip(b.iter()).fold(0, |acc, (x, y)| acc | (x ^ y));
+
+    diff == 0
+}
+
+/// Splits 24-word mnemonics into words 1-12 and 13-24, to be stored in two different places.
+///
+/// # Warning
+/// This is NOT secret sharing. Each half alone reveals 128 bits of the 256 bits of entropy,
+/// so whoever finds one half has to brute-force only the other one.
+/// Both halves are needed to restore the wallet, losing either of them loses the funds.
+pub fn split_halves(mnemonics: &[String]) -> Result<(Vec<String>, Vec<String>), Bip39Error> {
+    if WordsCount::try_from(mnemonics.len())? != WordsCount::_24 {
+        return Err(Bip39Error::InvalidWordsCount(mnemonics.len()));
+    }
+
+    let (first, second) = mnemonics.split_at(12);
+
+    Ok((first.to_vec(), second.to_vec()))
+}
+
+/// Splits entropy into a random pad and entropy XOR pad, each can be encoded as its own mnemonics.
+///
+/// Unlike `split_halves` this is a one-time pad, a single share reveals nothing about
+/// the entropy. Both shares are needed to restore it with `xor_combine`, losing either
+/// of them loses the funds. `rng` has to be cryptographically secure.
+pub fn xor_split(entropy: &[u8], rng: &mut dyn RngCore) -> (Vec<u8>, Vec<u8>) {
+    let mut pad = vec![0; entropy.len()];
+    rng.fill_bytes(&mut pad);
+
+    let masked = xor_bytes(entropy, &pad);
+
+    (pad, masked)
+}
+
+/// Restores entropy from the shares made by `xor_split`.
+/// Shares usually come from the user, so different lengths are reported, not truncated.
+pub fn xor_combine(a: &[u8], b: &[u8]) -> Result<Vec<u8>, Bip39Error> {
+    if a.len() != b.len() {
+        return Err(Bip39Error::ShareLengthMismatch(a.len(), b.len()));
     }
+
+    Ok(xor_bytes(a, b))
+}
+
+fn xor_bytes(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
 }
 
 /// Generates seed from given mnemonics, can be used later in HD wallets
-pub fn generate_master_seed(mnemonics: &[String]) -> Result<Seed, String> {
-    generate_master_seed_with_password(mnemonics, "")
+pub fn generate_master_seed(mnemonic: &Mnemonic) -> Seed {
+    generate_master_seed_with_password(mnemonic, &Passphrase::empty())
 }
 
 /// Generates seed from given mnemonics, and password. Can be used later in HD wallets
-pub fn generate_master_seed_with_password(
+pub fn generate_master_seed_with_password(mnemonic: &Mnemonic, user_password: &Passphrase) -> Seed {
+    Zeroizing::new(master_seed_array(mnemonic, user_password)).to_vec()
+}
+
+/// Generates seed from given mnemonics, and password. Returns it on the stack,
+/// with the length guaranteed at compile time.
+pub fn master_seed_array(mnemonic: &Mnemonic, user_password: &Passphrase) -> [u8; 64] {
+    let mut seed = [0; 64];
+    generate_master_seed_into(mnemonic, user_password, &mut seed);
+
+    seed
+}
+
+/// Generates seed from given mnemonics, and password, writes it to the caller provided buffer.
+/// Useful when seed goes directly to the secure storage, no copy is left on the heap.
+///
+/// Words are joined with the separator of the language, sentence is NFKD normalized,
+/// passphrase is normalized by `Passphrase`.
+pub fn generate_master_seed_into(
+    mnemonic: &Mnemonic,
+    user_password: &Passphrase,
+    out: &mut [u8; 64],
+) {
+    let sentence = normalized_sentence(mnemonic.words(), mnemonic.language());
+
+    sentence_to_seed(&sentence, user_password, out);
+}
+
+/// Generates seed from mnemonics made with any dictionary, e.g. `Wordlist::custom`,
+/// which `Mnemonic` cannot hold. Words and checksum are checked as `Mnemonic` does.
+pub fn generate_master_seed_with(
     mnemonics: &[String],
-    user_password: &str,
-) -> Result<Seed, String> {
-    let salt = format!("mnemonic{}", user_password);
-    let iterations = 2048;
-    let password = mnemonics.join(" ");
+    wordlist: &Wordlist,
+    user_password: &Passphrase,
+) -> Result<Seed, Bip39Error> {
+    if !is_checksum_valid_with(mnemonics, wordlist)? {
+        return Err(Bip39Error::InvalidChecksum);
+    }
+
+    let sentence = normalized_sentence(mnemonics, Language::English);
+    let mut seed = Zeroizing::new([0; 64]);
+
+    sentence_to_seed(&sentence, user_password, &mut seed);
+
+    Ok(seed.to_vec())
+}
+
+/// Words joined with the separator of the language, NFKD normalized, as fed to PBKDF2
+pub(super) fn normalized_sentence<S: Borrow<str>>(
+    words: &[S],
+    lang: Language,
+) -> Zeroizing<String> {
+    let sentence = Zeroizing::new(words.join(lang.separator()));
+
+    Zeroizing::new(sentence.nfkd().collect())
+}
+
+/// PBKDF2 of already joined and normalized mnemonic sentence
+pub(super) fn sentence_to_seed(sentence: &str, user_password: &Passphrase, out: &mut [u8; 64]) {
+    let salt = Zeroizing::new(format!("mnemonic{}", user_password.as_str()));
 
-    let mut seed: Seed = vec![0; 64];
     // Use low level api - can be used in [nostd] environment.
-    pbkdf2::<Hmac<Sha512>>(password.as_bytes(), salt.as_bytes(), iterations, &mut seed);
+    pbkdf2::<Hmac<Sha512>>(sentence.as_bytes(), salt.as_bytes(), PBKDF2_ROUNDS, out);
+}
+
+/// Generates seed from mnemonics in the given language. Words in any Unicode normalization
+/// form are accepted, words count, words and checksum are checked before PBKDF2 runs.
+#[deprecated(note = "use `Mnemonic::from_words` and `Mnemonic::to_seed`")]
+pub fn generate_master_seed_in(
+    mnemonics: &[String],
+    lang: Language,
+    user_password: &Passphrase,
+) -> Result<Seed, Bip39Error> {
+    let words = mnemonics.iter().map(|word| word.nfkd().collect()).collect();
+    let mnemonic = Mnemonic::from_words(words, lang)?;
+
+    Ok(generate_master_seed_with_password(&mnemonic, user_password))
+}
+
+/// Generates seed from word indices, e.g. kept by a hardware wallet instead of the words.
+/// Indices are validated as words of `Mnemonic`, checksum included.
+pub fn seed_from_indices(
+    indices: &[usize],
+    lang: Language,
+    passphrase: &str,
+) -> Result<Seed, Bip39Error> {
+    Ok(Mnemonic::from_indices(indices.to_vec(), lang)?.to_seed(&Passphrase::new(passphrase)))
+}
+
+/// Encodes entropy of the mnemonics with the dictionary of another language,
+/// e.g. English words to the Japanese ones on the same positions.
+/// Language translated from is `mnemonic.language()`, only the target one is given.
+///
+/// # Warning
+/// Translated mnemonics restore a *different* wallet: seed is derived from the text
+/// of the phrase, not from the entropy, so only the entropy is the same.
+pub fn translate_mnemonic(mnemonic: &Mnemonic, to: Language) -> Result<Mnemonic, Bip39Error> {
+    Mnemonic::from_indices(mnemonic.indices().to_vec(), to)
+}
+
+/// Checks if two phrases, with their passphrases, restore the same wallet.
+///
+/// Phrases are compared after normalization (whitespace, case), so the same mnemonics
+/// typed differently are the same wallet. Compares seeds, master key is derived from
+/// the seed only, so equal seeds mean equal master keys.
+pub fn same_wallet(a: &str, pa: &str, b: &str, pb: &str) -> Result<bool, Bip39Error> {
+    let seed = |phrase: &str, passphrase: &str| {
+        // Reject typos, instead of silently deriving a different wallet
+        let mnemonic = Mnemonic::from_phrase(phrase)?;
+
+        Ok::<_, Bip39Error>(Zeroizing::new(master_seed_array(
+            &mnemonic,
+            &Passphrase::new(passphrase),
+        )))
+    };
+
+    Ok(*seed(a, pa)? == *seed(b, pb)?)
+}
+
+/// Measures how long derivation of a single seed takes on this machine,
+/// e.g. to size progress bars or decide on parallelism before deriving many seeds.
+pub fn benchmark_seed_derivation() -> Duration {
+    // Throwaway mnemonics, well-known test vector
+    let mut words = vec!["abandon".to_string(); 11];
+    words.push("about".to_string());
+    let mnemonic =
+        Mnemonic::from_words(words, Language::English).expect("Test vector is not valid");
+
+    let start = Instant::now();
+    let _seed = generate_master_seed(&mnemonic);
+
+    start.elapsed()
+}
 
-    Ok(seed)
+/// Calibrates how many PBKDF2-HMAC-SHA512 iterations fit in the `budget` on this machine.
+///
+/// BIP39 fixes the count to 2048, this is a helper for devices wanting to cap latency,
+/// in case non-standard iteration counts are ever supported.
+pub fn iterations_for_budget(budget: Duration) -> u32 {
+    const CALIBRATION_ROUNDS: u32 = 2048;
+    let mut out = [0; 64];
+
+    let start = Instant::now();
+    pbkdf2::<Hmac<Sha512>>(b"calibration", b"mnemonic", CALIBRATION_ROUNDS, &mut out);
+    let elapsed = start.elapsed().as_nanos().max(1);
+
+    let rounds = budget.as_nanos() * CALIBRATION_ROUNDS as u128 / elapsed;
+
+    u32::try_from(rounds).unwrap_or(u32::MAX)
+}
+
+/// Always fails - there is no way back from the seed to mnemonics.
+///
+/// Seed is an output of PBKDF2, which is one-way, so if only seed is known,
+/// mnemonics (and the entropy) are lost for good. Function exists to make that explicit.
+pub fn seed_to_mnemonic(_seed: &[u8]) -> Result<Mnemonic, Bip39Error> {
+    Err(Bip39Error::Irreversible)
 }
 
 /// Uses entropy to generate indices for given ```word_count``` words
-fn generate_word_indices(word_count: WordsCount, ent: &dyn EntropySource) -> Vec<usize> {
-    let entropy_len = word_count as usize / 3 * 32;
+fn generate_word_indices(
+    word_count: WordsCount,
+    ent: &dyn EntropySource,
+) -> Result<Vec<usize>, Bip39Error> {
+    let entropy_len = word_count.keyspace_bits();
 
     debug!("Total bits {}", entropy_len);
 
-    let entropy = ent.get_random_bits(entropy_len);
-    debug!("Entropy {:x?}", entropy);
+    let entropy = ent.try_get_random_bits(entropy_len)?;
+
+    entropy_to_word_indices(entropy)
+}
+
+/// Calculates checksum of the entropy, and splits both to 11 bit word indices
+fn entropy_to_word_indices(entropy: Vec<u8>) -> Result<Vec<usize>, Bip39Error> {
+    debug!("Entropy {:x?}", Redacted::new(&entropy));
+
+    let entropy_len = entropy.len() * 8;
 
     // A checksum is generated by taking the first
     //  ENT / 32  bits of its SHA256 hash.
-    let checksum_len = entropy_len / 32;
+    let checksum_len = entropy.len() * 8 / 32;
     // Calculate hash, convert to bit string, get first checksum_len bits
     let checksum = &BinaryString::from(Sha256::digest(&entropy).as_slice()).0[..checksum_len];
 
@@ -181,55 +938,89 @@ fn generate_word_indices(word_count: WordsCount, ent: &dyn EntropySource) -> Vec
     // This checksum is appended to the end of the initial entropy.
     let entropy_bits = BinaryString::from(entropy).0 + checksum;
 
-    debug!("Raw binary: {}", entropy_bits);
+    debug!("Raw binary: {}", Redacted::new(&entropy_bits));
+
+    // BinaryString is expected to give exactly 8 bits per byte, with leading zeros,
+    // if that ever changes, slicing below would silently produce wrong indices
+    if entropy_bits.len() != entropy_len + checksum_len {
+        return Err(Bip39Error::Internal(format!(
+            "Expected {} bits of entropy and checksum, got {}",
+            entropy_len + checksum_len,
+            entropy_bits.len()
+        )));
+    }
 
     // Next, these concatenated bits are split into groups of 11 bits,
     // each encoding a number from 0-2047, serving as an index into a wordlist.
     let mut word_indices = vec![];
 
-    for start in (0..entropy_bits.len()).step_by(11) {
+    for start in (0..entropy_bits.len()).step_by(BITS_PER_WORD) {
         // Get 11 bits and convert to decimal
-        let bits = &entropy_bits[start..start + 11];
+        let bits = &entropy_bits[start..start + BITS_PER_WORD];
         word_indices.push(usize::from_str_radix(&bits, 2).unwrap());
     }
-    debug!("Word indexes: {:?}", word_indices);
+    debug!("Word indexes: {:?}", Redacted::new(&word_indices));
 
-    word_indices
+    Ok(word_indices)
 }
 
-/// Converts indices to actual mnemonics collection
-fn get_words_from_file(indices: &[usize]) -> Mnemonics {
-    // Convert indices to actual words
-    let words = get_dictionary();
-
-    let word_count = indices.len();
-    let mut found_memos = 0;
-
-    let mut mnemonics = vec![String::new(); word_count];
-    // Read the file line by line using the lines() iterator from std::io::BufRead.
-    'file_loop: for (index, word) in words.iter().enumerate() {
-        // Iterate over indices, check if any element matches, if so,
-        // put in mnemonics on 'position'
-        for (position, i) in indices.iter().enumerate() {
-            // TODO: how to get rid off * here?
-            if *i == index {
-                mnemonics[position] = String::from(word);
-                found_memos += 1;
-
-                // 'sort of' optimization, if all words are found - break
-                if found_memos == word_count {
-                    debug!("Breaking the loop at idx {}", index);
-
-                    // TODO: this smells like a goto, but smell is nice
-                    break 'file_loop;
-                }
-            }
+/// Converts raw entropy of 128 - 256 bits (multiple of 32) to mnemonics
+fn entropy_to_mnemonics_in(
+    entropy: Vec<u8>,
+    wordlist: &Wordlist,
+) -> Result<Vec<String>, Bip39Error> {
+    match entropy.len() {
+        16 | 20 | 24 | 28 | 32 => {}
+        len => {
+            return Err(Bip39Error::InvalidEntropy(format!(
+                "{} bits, expected 128 - 256 bits, multiple of 32",
+                len * 8
+            )))
         }
     }
 
-    debug!("Mnemonics {:?}", mnemonics);
+    let indices = entropy_to_word_indices(entropy)?;
+
+    Ok(indices_to_words_in(&indices, wordlist))
+}
+
+/// Generates mnemonics from the entropy supplied by the caller, e.g. dice rolls
+/// or hardware TRNG dump, without any `EntropySource`.
+/// Entropy has to be 128, 160, 192, 224 or 256 bits long.
+pub fn generate_mnemonics_from_entropy(entropy: &[u8]) -> Result<Mnemonic, Bip39Error> {
+    generate_mnemonics_from_entropy_in(entropy, Language::English)
+}
+
+/// Same as `generate_mnemonics_from_entropy`, with the dictionary of the given language
+pub fn generate_mnemonics_from_entropy_in(
+    entropy: &[u8],
+    lang: Language,
+) -> Result<Mnemonic, Bip39Error> {
+    let words = entropy_to_mnemonics_in(entropy.to_vec(), Wordlist::for_language(lang)?)?;
+
+    Mnemonic::from_words(words, lang)
+}
+
+/// Converts indices to actual mnemonics collection
+fn indices_to_words(indices: &[usize]) -> Vec<String> {
+    indices_to_words_in(indices, Wordlist::english())
+}
+
+fn indices_to_words_in(indices: &[usize], wordlist: &Wordlist) -> Vec<String> {
+    // Convert indices to actual words, indices are 11 bits wide, so always found
+    let mnemonics: Vec<String> = indices
+        .iter()
+        .map(|&index| {
+            u16::try_from(index)
+                .ok()
+                .and_then(|index| wordlist.word(index))
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect();
+
+    debug!("Mnemonics {:?}", Redacted::new(&mnemonics));
 
-    // TODO: mnemonics is of type Vec<String> isn't it better to be Vec<&String> ??
     mnemonics
 }
 
@@ -243,26 +1034,236 @@ fn get_words_from_file(indices: &[usize]) -> Mnemonics {
 ///
 /// # Example
 /// ```
+/// # use wallet_rs::bip39::{self, WordsCount};
+/// # use wallet_rs::entropy::BasicEntropy;
 /// let ent = BasicEntropy;
 /// let mnemonics = bip39::generate_mnemonics(WordsCount::_12, &ent).unwrap();
 /// ```
 ///
-pub fn generate_mnemonics(word_count: WordsCount, ent: &dyn EntropySource) -> Mnemonics {
-    let indices = generate_word_indices(word_count, ent);
+pub fn generate_mnemonics(
+    word_count: WordsCount,
+    ent: &dyn EntropySource,
+) -> Result<Mnemonic, Bip39Error> {
+    generate_mnemonics_in(word_count, ent, Language::English)
+}
 
-    get_words_from_file(&indices)
+/// Same as `generate_mnemonics`, with the dictionary of the given language
+pub fn generate_mnemonics_in(
+    word_count: WordsCount,
+    ent: &dyn EntropySource,
+    lang: Language,
+) -> Result<Mnemonic, Bip39Error> {
+    lang.ensure_enabled()?;
+    let indices = generate_word_indices(word_count, ent)?;
+
+    Mnemonic::from_indices(indices, lang)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Same as `generate_mnemonics`, with any dictionary, e.g. `Wordlist::custom`.
+/// Returns plain words, as `Mnemonic` holds only standard dictionaries,
+/// seed is derived from them with `generate_master_seed_with`.
+pub fn generate_mnemonics_with(
+    word_count: WordsCount,
+    ent: &dyn EntropySource,
+    wordlist: &Wordlist,
+) -> Result<Vec<String>, Bip39Error> {
+    let indices = generate_word_indices(word_count, ent)?;
+
+    Ok(indices_to_words_in(&indices, wordlist))
+}
 
-    struct DummyEntropy<'a> {
-        input: &'a str,
+/// Generates `count` mnemonics from a single draw of entropy.
+///
+/// Order is stable: `result[i]` is made from the i-th `word_count.keyspace_bits()` slice
+/// of the entropy, so the same entropy always gives the same list in the same order.
+pub fn generate_many(
+    count: usize,
+    word_count: WordsCount,
+    ent: &dyn EntropySource,
+) -> Result<Vec<Mnemonic>, Bip39Error> {
+    let bytes_per_mnemonic = word_count.keyspace_bits() / 8;
+    let entropy = ent.try_get_random_bits(count * word_count.keyspace_bits())?;
+
+    if entropy.len() != count * bytes_per_mnemonic {
+        return Err(Bip39Error::EntropyUnavailable(format!(
+            "Expected {} bytes, got {}",
+            count * bytes_per_mnemonic,
+            entropy.len()
+        )));
     }
 
-    impl<'a> EntropySource for DummyEntropy<'a> {
-        fn get_random_bits(&self, _count: usize) -> Vec<u8> {
+    entropy
+        .chunks(bytes_per_mnemonic)
+        .map(|chunk| {
+            let indices = entropy_to_word_indices(chunk.to_vec())?;
+            Mnemonic::from_indices(indices, Language::English)
+        })
+        .collect()
+}
+
+/// Writes down every step of mnemonics generation from the given entropy: entropy,
+/// its SHA256, checksum bits, each 11 bit group with its value and word, and the phrase.
+///
+/// Same entropy always gives byte-identical transcript, so two auditors can compare them
+/// offline. Transcript contains the secret, treat it like the mnemonics.
+pub fn generation_transcript(ent_hex: &str, word_count: WordsCount) -> Result<String, Bip39Error> {
+    let entropy = hex::decode(ent_hex).map_err(|e| Bip39Error::InvalidEntropy(e.to_string()))?;
+
+    if entropy.len() * 8 != word_count.keyspace_bits() {
+        return Err(Bip39Error::InvalidEntropy(format!(
+            "{} words need {} bits, got {}",
+            word_count as usize,
+            word_count.keyspace_bits(),
+            entropy.len() * 8
+        )));
+    }
+
+    let hash = Sha256::digest(&entropy);
+    let checksum_len = entropy.len() * 8 / 32;
+    let checksum = &BinaryString::from(hash.as_slice()).0[..checksum_len];
+
+    let indices = entropy_to_word_indices(entropy.clone())?;
+    let mnemonics = indices_to_words(&indices);
+
+    let mut transcript = String::new();
+    writeln!(transcript, "Entropy: {}", hex::encode(&entropy)).unwrap();
+    writeln!(transcript, "SHA256: {}", hex::encode(hash)).unwrap();
+    writeln!(transcript, "Checksum bits: {}", checksum).unwrap();
+
+    for (i, (index, word)) in indices.iter().zip(&mnemonics).enumerate() {
+        writeln!(
+            transcript,
+            "{:2}. {:0width$b} {:4} {}",
+            i + 1,
+            index,
+            index,
+            word,
+            width = BITS_PER_WORD
+        )
+        .unwrap();
+    }
+
+    writeln!(transcript, "Mnemonics: {}", mnemonics.join(" ")).unwrap();
+
+    Ok(transcript)
+}
+
+/// Generates mnemonics starting with the given word.
+///
+/// Draws entropy until the first 11 bits point to the `first` word in the dictionary,
+/// gives up after `max_tries`. On average 2048 tries are needed.
+/// Fixing the first word removes 11 bits of entropy.
+pub fn generate_with_first_word(
+    first: &str,
+    word_count: WordsCount,
+    ent: &dyn EntropySource,
+    max_tries: usize,
+) -> Result<Mnemonic, Bip39Error> {
+    let target = word_index(first, Language::English)
+        .ok_or_else(|| Bip39Error::InvalidWord(first.to_string()))? as usize;
+
+    for _ in 0..max_tries {
+        let indices = generate_word_indices(word_count, ent)?;
+
+        if indices[0] == target {
+            return Mnemonic::from_indices(indices, Language::English);
+        }
+    }
+
+    Err(Bip39Error::TriesExhausted(max_tries))
+}
+
+/// Dictionary words sounding alike, the starting point for `generate_unambiguous`
+pub const HOMOPHONE_WORDS: [&str; 6] = ["pair", "pear", "peace", "piece", "right", "write"];
+
+/// Generates mnemonics without any of the `confusable` words, e.g. `HOMOPHONE_WORDS`,
+/// for backups that are read aloud or handwritten.
+///
+/// Draws entropy until no word is confusable, gives up after `max_tries`.
+///
+/// # Warning
+/// Not a BIP39 feature, resulting mnemonics are valid, but excluding words reduces
+/// the keyspace, the more words are excluded, the more it shrinks.
+pub fn generate_unambiguous(
+    word_count: WordsCount,
+    ent: &dyn EntropySource,
+    confusable: &[&str],
+    max_tries: usize,
+) -> Result<Mnemonic, Bip39Error> {
+    for _ in 0..max_tries {
+        let mnemonic =
+            Mnemonic::from_indices(generate_word_indices(word_count, ent)?, Language::English)?;
+
+        if !mnemonic.iter().any(|word| confusable.contains(&word)) {
+            return Ok(mnemonic);
+        }
+    }
+
+    Err(Bip39Error::TriesExhausted(max_tries))
+}
+
+/// Generates mnemonics which checksum starts with the given 4 bits,
+/// so backups can be filed by the checksum.
+///
+/// Draws entropy until first 4 bits of the checksum match `nibble`,
+/// gives up after `max_tries`. On average 16 tries are needed.
+pub fn generate_with_checksum_prefix(
+    nibble: u8,
+    word_count: WordsCount,
+    ent: &dyn EntropySource,
+    max_tries: usize,
+) -> Result<Mnemonic, Bip39Error> {
+    if nibble > 0x0f {
+        return Err(Bip39Error::InvalidNibble(nibble));
+    }
+
+    for _ in 0..max_tries {
+        let entropy = ent.try_get_random_bits(word_count.keyspace_bits())?;
+
+        if Sha256::digest(&entropy)[0] >> 4 == nibble {
+            let indices = entropy_to_word_indices(entropy)?;
+            return Mnemonic::from_indices(indices, Language::English);
+        }
+    }
+
+    Err(Bip39Error::TriesExhausted(max_tries))
+}
+
+/// Generates mnemonics as defined in BIP39, using any `RngCore` as a source of entropy.
+///
+/// Convenient for deterministic tests, where seeded RNG, or `StepRng` can be passed
+/// instead of implementing `EntropySource`.
+pub fn generate_mnemonics_from_rng<R: RngCore>(
+    word_count: WordsCount,
+    rng: &mut R,
+) -> Result<Mnemonic, Bip39Error> {
+    let mut entropy = vec![0; word_count.keyspace_bits() / 8];
+    rng.try_fill_bytes(&mut entropy)
+        .map_err(|e| Bip39Error::EntropyUnavailable(e.to_string()))?;
+
+    let indices = entropy_to_word_indices(entropy)?;
+
+    Mnemonic::from_indices(indices, Language::English)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entropy::BasicEntropy;
+    use rand::rngs::mock::StepRng;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::cell::RefCell;
+    use std::fs::File;
+    use std::io::BufReader;
+    use std::path::PathBuf;
+
+    struct DummyEntropy<'a> {
+        input: &'a str,
+    }
+
+    impl<'a> EntropySource for DummyEntropy<'a> {
+        fn get_random_bits(&self, _count: usize) -> Vec<u8> {
             hex::decode(&self.input).expect("Decoding failed")
         }
     }
@@ -276,6 +1277,27 @@ mod tests {
         }
     }
 
+    /// Deterministic, but random looking entropy
+    struct SeededEntropy {
+        rng: RefCell<StdRng>,
+    }
+
+    impl EntropySource for SeededEntropy {
+        fn get_random_bits(&self, count: usize) -> Vec<u8> {
+            let mut out = vec![0; count / 8];
+            self.rng.borrow_mut().fill_bytes(&mut out);
+            out
+        }
+    }
+
+    impl Default for SeededEntropy {
+        fn default() -> Self {
+            SeededEntropy {
+                rng: RefCell::new(StdRng::seed_from_u64(156)),
+            }
+        }
+    }
+
     #[derive(Serialize, Deserialize, Debug)]
     struct TestElement {
         ent: String,
@@ -309,12 +1331,207 @@ mod tests {
         assert_eq!(
             mnemonics,
             generate_mnemonics(WordsCount::_12, &DummyEntropy::default())
+                .unwrap()
+                .words()
+        );
+    }
+
+    #[test_env_log::test]
+    fn generate_mnemonics_from_rng_is_deterministic() {
+        // StepRng fills bytes with consecutive little endian u64 values: 0, 1
+        let mut rng = StepRng::new(0, 1);
+        let ent = DummyEntropy {
+            input: "00000000000000000100000000000000",
+        };
+
+        assert_eq!(
+            generate_mnemonics(WordsCount::_12, &ent),
+            generate_mnemonics_from_rng(WordsCount::_12, &mut rng)
+        );
+    }
+
+    #[test_env_log::test]
+    fn generate_many_keeps_entropy_order() {
+        // Two vectors from vectors.json, one after another
+        let ent = DummyEntropy {
+            input: "000000000000000000000000000000007f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+        };
+
+        let result = generate_many(2, WordsCount::_12, &ent).unwrap();
+
+        assert_eq!(2, result.len());
+        assert_eq!(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+             abandon about",
+            result[0].to_string()
+        );
+        assert_eq!(
+            "legal winner thank year wave sausage worth useful legal winner thank yellow",
+            result[1].to_string()
+        );
+
+        assert!(matches!(
+            generate_many(3, WordsCount::_12, &ent),
+            Err(Bip39Error::EntropyUnavailable(_))
+        ));
+    }
+
+    #[test_env_log::test]
+    fn generation_transcript_works() {
+        let transcript =
+            generation_transcript("d5a58c5fded9ac099f432a253dbffb68", WordsCount::_12).unwrap();
+
+        assert!(transcript.contains("Entropy: d5a58c5fded9ac099f432a253dbffb68"));
+        assert!(transcript.contains("Checksum bits: 1010"));
+        assert!(transcript.contains(" 1. 11010101101 1709 stick\n"));
+        assert!(transcript.ends_with(
+            "Mnemonics: stick cluster blood sad onion age laptop grab cement unknown yard spend\n"
+        ));
+
+        assert_eq!(
+            Ok(transcript),
+            generation_transcript("d5a58c5fded9ac099f432a253dbffb68", WordsCount::_12)
         );
+
+        assert!(matches!(
+            generation_transcript("d5a58c5fded9ac099f432a253dbffb68", WordsCount::_24),
+            Err(Bip39Error::InvalidEntropy(_))
+        ));
+    }
+
+    #[test_env_log::test]
+    fn generate_with_first_word_works() {
+        let ent = SeededEntropy::default();
+
+        let mnemonic = generate_with_first_word("ocean", WordsCount::_12, &ent, 100_000).unwrap();
+        assert_eq!("ocean", mnemonic.words()[0]);
+
+        assert_eq!(
+            Err(Bip39Error::TriesExhausted(0)),
+            generate_with_first_word("ocean", WordsCount::_12, &ent, 0)
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidWord("slick".to_string())),
+            generate_with_first_word("slick", WordsCount::_12, &ent, 1)
+        );
+    }
+
+    #[test_env_log::test]
+    fn binary_string_gives_8_bits_per_byte() {
+        for word_count in WordsCount::all() {
+            // Leading zeros in each byte are most likely to be dropped
+            let entropy = vec![0x01; word_count.keyspace_bits() / 8];
+
+            let indices = entropy_to_word_indices(entropy).unwrap();
+            assert_eq!(word_count as usize, indices.len());
+        }
+    }
+
+    #[test_env_log::test]
+    fn generate_unambiguous_works() {
+        let ent = SeededEntropy::default();
+        let words = get_dictionary();
+        // About 6% of the dictionary, so some draws are rejected
+        let confusable: Vec<&str> = words
+            .iter()
+            .filter(|word| word.starts_with('a'))
+            .map(String::as_str)
+            .collect();
+
+        for _ in 0..10 {
+            let mnemonic = generate_unambiguous(WordsCount::_12, &ent, &confusable, 100).unwrap();
+
+            assert!(mnemonic.iter().all(|word| !word.starts_with('a')));
+        }
+
+        let all: Vec<&str> = words.iter().map(String::as_str).collect();
+        assert_eq!(
+            Err(Bip39Error::TriesExhausted(10)),
+            generate_unambiguous(WordsCount::_12, &ent, &all, 10)
+        );
+
+        for word in &HOMOPHONE_WORDS {
+            assert!(word_index(word, Language::English).is_some());
+        }
+    }
+
+    #[test_env_log::test]
+    fn generate_with_checksum_prefix_works() {
+        let ent = SeededEntropy::default();
+        let words = get_dictionary();
+
+        let mnemonic = generate_with_checksum_prefix(0x0a, WordsCount::_12, &ent, 1000).unwrap();
+
+        // For 12 words, checksum is the last 4 bits of the last word
+        let last = words
+            .iter()
+            .position(|el| el == &mnemonic.words()[11])
+            .unwrap();
+        assert_eq!(0x0a, last & 0x0f);
+
+        assert_eq!(
+            Err(Bip39Error::InvalidNibble(16)),
+            generate_with_checksum_prefix(16, WordsCount::_12, &ent, 1000)
+        );
+    }
+
+    #[test_env_log::test]
+    fn round_trip_for_15_18_21_words() {
+        // Checksum of 5, 6, 7 bits does not align to nibble, 18 words are from vectors.json,
+        // 15 and 21 words are generated with python-mnemonic, passphrase "TREZOR"
+        let vectors = [
+            (
+                "9e885d952ad362caeb4efe34a8e91bd2f55c11d0",
+                "ozone drill grab fiber curtain grace pudding thank cruise elder eight planet \
+                 fiction angry donor",
+                "9bd53fa7f1a5008ff1c42b32148058cd311673ed30a8a258ce0f7c94bab736ff\
+                 b98a427e29be354a6a720d12bd6abbd6f54f4467fc204bf8de4c29c012d68af7",
+            ),
+            (
+                "6d9be1ee6ebd27a258115aad99b7317b9c8d28b6d76431c3",
+                "horn tenant knee talent sponsor spell gate clip pulse soap slush warm silver \
+                 nephew swap uncle crack brave",
+                "fd579828af3da1d32544ce4db5c73d53fc8acc4ddb1e3b251a31179cdb71e853\
+                 c56d2fcb11aed39898ce6c34b10b5382772db8796e52837b54468aeb312cfc3d",
+            ),
+            (
+                "18ab19a9f54a9274f03e5209a2ac8a91df2a8b3f3b2a1d6b7c9e5a13",
+                "board flee heavy tunnel powder denial science ski answer betray cargo casual \
+                 verify easily woman grab buddy fossil chest reflect defense",
+                "e14a743590567e099efe33bcacffa5fa4b1bbeaf8cfa8ac6897be395cd9774a5\
+                 73a10476235eda889dac622b489feb34711900bfa9a1ed545629e159d1c380e4",
+            ),
+        ];
+        let words = get_dictionary();
+
+        for (ent, phrase, seed) in &vectors {
+            let mnemonics: Vec<String> = phrase.split_whitespace().map(String::from).collect();
+            let word_count = WordsCount::try_from(mnemonics.len()).unwrap();
+
+            let mnemonic = generate_mnemonics(word_count, &DummyEntropy { input: ent }).unwrap();
+            assert_eq!(mnemonics, mnemonic.words());
+            assert_eq!(Ok(true), is_checksum_valid(&mnemonics));
+
+            let indices: Vec<usize> = mnemonics
+                .iter()
+                .map(|word| words.iter().position(|el| el == word).unwrap())
+                .collect();
+            let (entropy, _checksum) = indices_to_entropy(&indices);
+            assert_eq!(*ent, hex::encode(entropy));
+
+            assert_eq!(
+                hex::decode(seed).unwrap(),
+                generate_master_seed_with_password(&mnemonic, &Passphrase::new("TREZOR"))
+            );
+        }
     }
 
     #[test_env_log::test]
     fn is_checksum_valid_works() {
-        let mut mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default());
+        let mut mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default())
+            .unwrap()
+            .words()
+            .to_vec();
 
         assert_eq!(is_checksum_valid(&mnemonics).unwrap(), true);
 
@@ -324,7 +1541,10 @@ mod tests {
 
     #[test_env_log::test]
     fn is_checksum_valid_returns_error_on_invalid_word() {
-        let mut mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default());
+        let mut mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default())
+            .unwrap()
+            .words()
+            .to_vec();
         mnemonics[0] = "slick".to_string();
         assert_eq!(
             is_checksum_valid(&mnemonics),
@@ -332,9 +1552,318 @@ mod tests {
         );
     }
 
+    #[test_env_log::test]
+    fn checksum_status_works() {
+        let mut mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default())
+            .unwrap()
+            .words()
+            .to_vec();
+        assert_eq!(ChecksumStatus::Valid, checksum_status(&mnemonics));
+
+        // Last word "spend" is 1674, so 0b1010 is in the mnemonics, entropy changed by the
+        // first word gives 0b0010
+        mnemonics[0] = "spend".to_string();
+        assert_eq!(
+            ChecksumStatus::Invalid {
+                expected: 0b0010,
+                got: 0b1010
+            },
+            checksum_status(&mnemonics)
+        );
+
+        mnemonics[11] = "slick".to_string();
+        assert_eq!(
+            ChecksumStatus::UnknownWord("slick".to_string()),
+            checksum_status(&mnemonics)
+        );
+
+        mnemonics.pop();
+        assert_eq!(ChecksumStatus::WrongLength(11), checksum_status(&mnemonics));
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(11)),
+            is_checksum_valid(&mnemonics)
+        );
+    }
+
+    #[test_env_log::test]
+    fn decompose_works() {
+        let mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default())
+            .unwrap()
+            .words()
+            .to_vec();
+
+        assert_eq!(
+            Ok(Decomposition {
+                indices: vec![1709, 355, 191, 1517, 1238, 38, 1000, 810, 297, 1903, 2038, 1674],
+                entropy_bytes: hex::decode("d5a58c5fded9ac099f432a253dbffb68").unwrap(),
+                checksum_bits: vec![true, false, true, false],
+            }),
+            decompose(&mnemonics)
+        );
+
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(11)),
+            decompose(&mnemonics[1..])
+        );
+    }
+
+    #[test_env_log::test]
+    fn generate_mnemonics_from_entropy_works() {
+        let mnemonic = generate_mnemonics_from_entropy(&[0; 16]).unwrap();
+        assert_eq!(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+             abandon about",
+            mnemonic.to_string()
+        );
+
+        let mnemonic = generate_mnemonics_from_entropy(&[0xff; 32]).unwrap();
+        assert_eq!(WordsCount::_24, mnemonic.word_count());
+        assert_eq!(Some(&"vote".to_string()), mnemonic.words().last());
+        assert_eq!(vec![0xff; 32], mnemonic.entropy());
+
+        for invalid in &[0, 15, 17, 33] {
+            assert!(matches!(
+                generate_mnemonics_from_entropy(&vec![0; *invalid]),
+                Err(Bip39Error::InvalidEntropy(_))
+            ));
+        }
+    }
+
+    #[test_env_log::test]
+    fn valid_final_words_works() {
+        let mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default())
+            .unwrap()
+            .words()
+            .to_vec();
+
+        let final_words = valid_final_words(&mnemonics[..11]).unwrap();
+        assert_eq!(checksum_strength(WordsCount::_12).0, final_words.len());
+        assert!(final_words.contains(&mnemonics[11].as_str()));
+
+        for word in final_words {
+            let mut candidate = mnemonics[..11].to_vec();
+            candidate.push(word.to_string());
+            assert_eq!(Ok(true), is_checksum_valid(&candidate));
+        }
+
+        let first_words = vec!["zoo".to_string(); 23];
+        assert_eq!(8, valid_final_words(&first_words).unwrap().len());
+
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(12)),
+            valid_final_words(&mnemonics)
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidWord("slick".to_string())),
+            valid_final_words(&vec!["slick".to_string(); 11])
+        );
+    }
+
+    #[test_env_log::test]
+    fn mnemonic_to_entropy_works() {
+        let mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default())
+            .unwrap()
+            .words()
+            .to_vec();
+        assert_eq!(
+            Ok(hex::decode("d5a58c5fded9ac099f432a253dbffb68").unwrap()),
+            mnemonic_to_entropy(&mnemonics)
+        );
+
+        for length in &[16, 20, 24, 28, 32] {
+            let entropy: Vec<u8> = (0..*length as u8).map(|i| i.wrapping_mul(37)).collect();
+            let mnemonic = generate_mnemonics_from_entropy(&entropy).unwrap();
+
+            assert_eq!(Ok(entropy), mnemonic_to_entropy(mnemonic.words()));
+        }
+
+        let mut invalid = mnemonics.clone();
+        invalid.swap(0, 1);
+        assert_eq!(
+            Err(Bip39Error::InvalidChecksum),
+            mnemonic_to_entropy(&invalid)
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(11)),
+            mnemonic_to_entropy(&mnemonics[1..])
+        );
+    }
+
+    #[test_env_log::test]
+    fn looks_like_test_vector_flags_known_vectors() {
+        let mut mnemonics = vec!["abandon".to_string(); 12];
+        assert!(looks_like_test_vector(&mnemonics));
+
+        mnemonics[11] = "about".to_string();
+        assert!(looks_like_test_vector(&mnemonics));
+
+        let mnemonics: Vec<String> =
+            "legal winner thank year wave sausage worth useful legal winner thank yellow"
+                .split_whitespace()
+                .map(String::from)
+                .collect();
+        assert!(looks_like_test_vector(&mnemonics));
+
+        let mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default())
+            .unwrap()
+            .words()
+            .to_vec();
+        assert!(!looks_like_test_vector(&mnemonics));
+    }
+
+    #[test_env_log::test]
+    fn mnemonic_commitments_match_only_for_same_mnemonics() {
+        let mut mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default())
+            .unwrap()
+            .words()
+            .to_vec();
+        let commitment = mnemonic_commitment(&mnemonics);
+
+        assert!(commitments_match(
+            &commitment,
+            &mnemonic_commitment(&mnemonics)
+        ));
+
+        mnemonics[11] = "stick".to_string();
+        assert!(!commitments_match(
+            &commitment,
+            &mnemonic_commitment(&mnemonics)
+        ));
+    }
+
+    #[test_env_log::test]
+    fn mnemonic_commitment_is_over_canonical_phrase() {
+        let mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default())
+            .unwrap()
+            .words()
+            .to_vec();
+        let commitment = mnemonic_commitment(&mnemonics);
+
+        // Upper case, extra whitespace, and full width letters which NFKD maps to ASCII
+        let mut typed = mnemonics.clone();
+        typed[0] = typed[0].to_uppercase();
+        typed[1] = format!(" {}\t", typed[1]);
+        typed[2] = typed[2]
+            .chars()
+            .map(|c| char::from_u32(c as u32 - 'a' as u32 + 'ａ' as u32).unwrap())
+            .collect();
+
+        assert_ne!(mnemonics, typed);
+        assert!(commitments_match(&commitment, &mnemonic_commitment(&typed)));
+    }
+
+    #[test_env_log::test]
+    fn split_halves_works() {
+        let mnemonics = generate_mnemonics(WordsCount::_24, &BasicEntropy)
+            .unwrap()
+            .words()
+            .to_vec();
+
+        let (first, second) = split_halves(&mnemonics).unwrap();
+        assert_eq!(12, first.len());
+        assert_eq!(12, second.len());
+        assert_eq!(mnemonics, [first, second].concat());
+
+        let mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default())
+            .unwrap()
+            .words()
+            .to_vec();
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(12)),
+            split_halves(&mnemonics)
+        );
+    }
+
+    #[test_env_log::test]
+    fn xor_split_round_trips() {
+        let mut rng = StdRng::seed_from_u64(156);
+        let entropy = hex::decode("d5a58c5fded9ac099f432a253dbffb68").unwrap();
+
+        let (pad, masked) = xor_split(&entropy, &mut rng);
+        assert_eq!(Ok(entropy.clone()), xor_combine(&pad, &masked));
+        assert_eq!(Ok(entropy), xor_combine(&masked, &pad));
+
+        // Both shares encode as valid mnemonics on their own
+        let ent = DummyEntropy {
+            input: &hex::encode(&masked),
+        };
+        assert!(generate_mnemonics(WordsCount::_12, &ent).is_ok());
+    }
+
+    #[test_env_log::test]
+    fn xor_combine_rejects_shares_of_different_length() {
+        assert_eq!(
+            Err(Bip39Error::ShareLengthMismatch(16, 32)),
+            xor_combine(&[0; 16], &[0; 32])
+        );
+    }
+
+    #[test_env_log::test]
+    fn xor_split_share_is_independent_of_entropy() {
+        let mut rng = StdRng::seed_from_u64(156);
+
+        // Masked share of all zero and all one entropy looks the same, half of the bits set
+        for entropy in &[[0x00; 32], [0xff; 32]] {
+            let ones: u32 = (0..100)
+                .flat_map(|_| xor_split(entropy, &mut rng).1)
+                .map(|byte| byte.count_ones())
+                .sum();
+
+            let ratio = ones as f64 / (100.0 * 256.0);
+            assert!((ratio - 0.5).abs() < 0.02, "Ratio of ones {}", ratio);
+        }
+    }
+
+    #[test_env_log::test]
+    fn recover_missing_words_works() {
+        let mnemonic = generate_mnemonics(WordsCount::_12, &DummyEntropy::default()).unwrap();
+
+        let mut words: Vec<Option<String>> = mnemonic.words().iter().cloned().map(Some).collect();
+        words[2] = None;
+
+        let recovered = recover_missing_words(&words, WordsCount::_12).unwrap();
+        assert!(recovered.contains(&mnemonic));
+        for candidate in &recovered {
+            assert_eq!(Ok(true), is_checksum_valid(candidate.words()));
+        }
+
+        words[0] = None;
+        words[1] = None;
+        assert_eq!(
+            Err(Bip39Error::TooManyUnknownWords(3)),
+            recover_missing_words(&words, WordsCount::_12)
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(12)),
+            recover_missing_words(&words, WordsCount::_24)
+        );
+    }
+
+    #[test_env_log::test]
+    fn indices_checksum_matches_string_checksum() {
+        let mut mnemonics = generate_mnemonics(WordsCount::_24, &BasicEntropy)
+            .unwrap()
+            .words()
+            .to_vec();
+        let words = get_dictionary();
+
+        for last in &["abandon", "zoo"] {
+            mnemonics[23] = last.to_string();
+            let indices: Vec<usize> = mnemonics
+                .iter()
+                .map(|memo| words.iter().position(|el| el == memo).unwrap())
+                .collect();
+
+            assert_eq!(
+                is_checksum_valid(&mnemonics).unwrap(),
+                is_indices_checksum_valid(&indices)
+            );
+        }
+    }
+
     #[test_env_log::test]
     fn generate_master_seed_works() {
-        let mnemonics = vec![
+        let words = vec![
             // TODO: how to avoid that .to_string() crap?
             "stick".to_string(),
             "cluster".to_string(),
@@ -352,12 +1881,173 @@ mod tests {
         let seed = "f3990aab57ffcba134df93414ce4246091a68598c6e06142dd3e62\
                     5990542bcc51f356971e33c98e597dc76590e1fa8b3a2e5e3195b6\
                     41d0ad34ddd5441dd0ec";
+        let mnemonic = Mnemonic::from_words(words, Language::English).unwrap();
+        assert_eq!(hex::decode(seed).unwrap(), generate_master_seed(&mnemonic));
+    }
+
+    #[test_env_log::test]
+    #[allow(deprecated)]
+    fn generate_master_seed_in_checks_mnemonics() {
+        let mnemonic = generate_mnemonics(WordsCount::_12, &DummyEntropy::default()).unwrap();
+        let passphrase = Passphrase::new("TREZOR");
+
+        assert_eq!(
+            Ok(generate_master_seed_with_password(&mnemonic, &passphrase)),
+            generate_master_seed_in(mnemonic.words(), Language::English, &passphrase)
+        );
+
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(0)),
+            generate_master_seed_in(&[], Language::English, &passphrase)
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(5)),
+            generate_master_seed_in(&mnemonic.words()[..5], Language::English, &passphrase)
+        );
+
+        let mut invalid = mnemonic.words().to_vec();
+        invalid[0] = "spend".to_string();
+        assert_eq!(
+            Err(Bip39Error::InvalidChecksum),
+            generate_master_seed_in(&invalid, Language::English, &passphrase)
+        );
+    }
+
+    #[test_env_log::test]
+    fn seed_from_indices_rejects_invalid_words_count() {
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(0)),
+            seed_from_indices(&[], Language::English, "")
+        );
+    }
+
+    #[test_env_log::test]
+    fn seed_does_not_depend_on_normalization_form() {
+        let mut composed = vec!["abandon".to_string(); 11];
+        composed.push("caf\u{e9}".to_string());
+        let mut decomposed = composed.clone();
+        decomposed[11] = "cafe\u{301}".to_string();
+
+        assert_eq!(
+            *normalized_sentence(&decomposed, Language::English),
+            *normalized_sentence(&composed, Language::English)
+        );
+        assert_eq!(
+            Passphrase::new("p\u{e4}ss").as_str(),
+            Passphrase::new("pa\u{308}ss").as_str()
+        );
+    }
+
+    #[test_env_log::test]
+    fn generate_master_seed_into_works() {
+        let mnemonic = generate_mnemonics(WordsCount::_12, &DummyEntropy::default()).unwrap();
+        let seed = "f3990aab57ffcba134df93414ce4246091a68598c6e06142dd3e62\
+                    5990542bcc51f356971e33c98e597dc76590e1fa8b3a2e5e3195b6\
+                    41d0ad34ddd5441dd0ec";
+
+        let mut out = [0; 64];
+        generate_master_seed_into(&mnemonic, &Passphrase::empty(), &mut out);
+        assert_eq!(hex::decode(seed).unwrap(), out.to_vec());
+    }
+
+    #[test_env_log::test]
+    fn master_seed_array_works() {
+        let mnemonic = generate_mnemonics(WordsCount::_12, &DummyEntropy::default()).unwrap();
+        let seed = "f3990aab57ffcba134df93414ce4246091a68598c6e06142dd3e62\
+                    5990542bcc51f356971e33c98e597dc76590e1fa8b3a2e5e3195b6\
+                    41d0ad34ddd5441dd0ec";
+
+        let array = master_seed_array(&mnemonic, &Passphrase::empty());
+        assert_eq!(hex::decode(seed).unwrap(), array.to_vec());
+    }
+
+    #[test_env_log::test]
+    fn empty_passphrase_gives_default_seed() {
+        let mnemonic = generate_mnemonics(WordsCount::_12, &DummyEntropy::default()).unwrap();
+
+        assert_eq!(
+            generate_master_seed(&mnemonic),
+            generate_master_seed_with_password(&mnemonic, &Passphrase::empty())
+        );
+        assert_eq!(
+            generate_master_seed(&mnemonic),
+            generate_master_seed_with_password(&mnemonic, &Passphrase::new(""))
+        );
+    }
+
+    #[test_env_log::test]
+    fn same_wallet_ignores_whitespace_and_case() {
+        let a = "stick cluster blood sad onion age laptop grab cement unknown yard spend";
+        let b = "  STICK cluster\tblood sad onion  age laptop grab Cement unknown yard spend\n";
+
+        assert_eq!(Ok(true), same_wallet(a, "", b, ""));
+        assert_eq!(Ok(true), same_wallet(a, "TREZOR", b, "TREZOR"));
+        assert_eq!(Ok(false), same_wallet(a, "", b, "TREZOR"));
+
+        assert_eq!(
+            Err(Bip39Error::InvalidWord("stik".to_string())),
+            same_wallet(a, "", &b.replace("STICK", "stik"), "")
+        );
+    }
+
+    #[test_env_log::test]
+    fn seed_from_indices_works() {
+        let indices = [
+            1709, 355, 191, 1517, 1238, 38, 1000, 810, 297, 1903, 2038, 1674,
+        ];
+        let mnemonic = generate_mnemonics(WordsCount::_12, &DummyEntropy::default()).unwrap();
+
+        assert_eq!(
+            generate_master_seed(&mnemonic),
+            seed_from_indices(&indices, Language::English, "").unwrap()
+        );
+        assert_eq!(
+            generate_master_seed_with_password(&mnemonic, &Passphrase::new("TREZOR")),
+            seed_from_indices(&indices, Language::English, "TREZOR").unwrap()
+        );
+
+        let mut invalid = indices;
+        invalid[11] = 2048;
+        assert_eq!(
+            Err(Bip39Error::InvalidWordIndex(2048)),
+            seed_from_indices(&invalid, Language::English, "")
+        );
+
+        invalid[11] = 1675;
+        assert_eq!(
+            Err(Bip39Error::InvalidChecksum),
+            seed_from_indices(&invalid, Language::English, "")
+        );
+
+        #[cfg(not(feature = "japanese"))]
         assert_eq!(
-            Ok(hex::decode(seed).unwrap()),
-            generate_master_seed(&mnemonics)
+            Err(Bip39Error::LanguageNotEnabled(Language::Japanese)),
+            seed_from_indices(&indices, Language::Japanese, "")
         );
     }
 
+    #[test_env_log::test]
+    fn benchmark_seed_derivation_works() {
+        assert!(benchmark_seed_derivation() > Duration::from_secs(0));
+    }
+
+    #[test_env_log::test]
+    fn iterations_for_budget_grows_with_budget() {
+        let small = iterations_for_budget(Duration::from_millis(10));
+        let large = iterations_for_budget(Duration::from_secs(1));
+
+        assert!(small > 0);
+        assert!(large > small, "{} <= {}", large, small);
+    }
+
+    #[test_env_log::test]
+    fn seed_to_mnemonic_is_irreversible() {
+        let mnemonic = generate_mnemonics(WordsCount::_12, &DummyEntropy::default()).unwrap();
+        let seed = generate_master_seed(&mnemonic);
+
+        assert_eq!(Err(Bip39Error::Irreversible), seed_to_mnemonic(&seed));
+    }
+
     #[test_env_log::test]
     fn cannot_convert_invalid_integer_to_words_count() {
         let invalid = 69;
@@ -367,6 +2057,250 @@ mod tests {
         );
     }
 
+    #[test_env_log::test]
+    fn wordlist_size_matches_bits_per_word() {
+        assert_eq!(WORDLIST_SIZE, 2usize.pow(BITS_PER_WORD as u32));
+        assert_eq!(WORDLIST_SIZE, get_dictionary().len());
+    }
+
+    #[test_env_log::test]
+    fn all_words_counts_are_listed() {
+        assert_eq!([12, 15, 18, 21, 24], WordsCount::values());
+        assert_eq!(
+            WordsCount::values().to_vec(),
+            WordsCount::all()
+                .map(|word_count| word_count as usize)
+                .collect::<Vec<_>>()
+        );
+
+        for word_count in WordsCount::all() {
+            // Entropy and checksum bits, 11 bits per word
+            let bits = word_count.keyspace_bits();
+            assert_eq!(
+                Ok(word_count),
+                WordsCount::try_from((bits + bits / 32) / 11)
+            );
+        }
+    }
+
+    #[test_env_log::test]
+    fn keyspace_is_reported_for_words_count() {
+        assert_eq!(256, WordsCount::_24.keyspace_bits());
+        assert_eq!(128, WordsCount::_12.keyspace_bits());
+        assert_eq!(
+            "2^256 possible 24-word wallets",
+            keyspace_description(WordsCount::_24)
+        );
+    }
+
+    #[test_env_log::test]
+    fn checksum_strength_works() {
+        assert_eq!((128, 2048), checksum_strength(WordsCount::_12));
+        assert_eq!((64, 2048), checksum_strength(WordsCount::_15));
+        assert_eq!((32, 2048), checksum_strength(WordsCount::_18));
+        assert_eq!((16, 2048), checksum_strength(WordsCount::_21));
+        assert_eq!((8, 2048), checksum_strength(WordsCount::_24));
+    }
+
+    #[test_env_log::test]
+    fn remaining_entropy_bits_works() {
+        assert_eq!(128.0, remaining_entropy_bits(0, WordsCount::_12));
+        assert_eq!(117.0, remaining_entropy_bits(1, WordsCount::_12));
+        // Last word: 11 bits, of which 4 are checksum, same as `checksum_strength`
+        assert_eq!(7.0, remaining_entropy_bits(11, WordsCount::_12));
+        assert_eq!(0.0, remaining_entropy_bits(12, WordsCount::_12));
+        assert_eq!(0.0, remaining_entropy_bits(100, WordsCount::_12));
+
+        for word_count in WordsCount::all() {
+            let (valid, _) = checksum_strength(word_count);
+            assert_eq!(
+                (valid as f64).log2(),
+                remaining_entropy_bits(word_count as usize - 1, word_count)
+            );
+        }
+    }
+
+    #[test_env_log::test]
+    fn to_plate_format_works() {
+        let mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default())
+            .unwrap()
+            .words()
+            .to_vec();
+        let plate = to_plate_format(&mnemonics).unwrap();
+
+        assert_eq!(12, plate.len());
+        assert_eq!((1, "stic".to_string(), 1710), plate[0]);
+        assert_eq!((4, "sad".to_string(), 1518), plate[3]);
+        assert_eq!((12, "spen".to_string(), 1675), plate[11]);
+
+        let mut mnemonics = vec!["abandon".to_string(); 11];
+        mnemonics.push("about".to_string());
+        let plate = to_plate_format(&mnemonics).unwrap();
+        assert_eq!((1, "aban".to_string(), 1), plate[0]);
+        assert_eq!((12, "abou".to_string(), 4), plate[11]);
+
+        mnemonics[0] = "slick".to_string();
+        assert_eq!(
+            Err(Bip39Error::InvalidWord("slick".to_string())),
+            to_plate_format(&mnemonics)
+        );
+    }
+
+    #[test_env_log::test]
+    fn mnemonics_with_prefix_count_works() {
+        let mnemonics = generate_mnemonics(WordsCount::_12, &DummyEntropy::default())
+            .unwrap()
+            .words()
+            .to_vec();
+
+        assert_eq!(
+            Ok(128),
+            mnemonics_with_prefix_count(&mnemonics[..11], WordsCount::_12)
+        );
+        assert_eq!(
+            Ok(2048 * 128),
+            mnemonics_with_prefix_count(&mnemonics[..10], WordsCount::_12)
+        );
+        assert_eq!(
+            Ok(1),
+            mnemonics_with_prefix_count(&mnemonics, WordsCount::_12)
+        );
+        // 4 more words, 8 bits of checksum
+        assert_eq!(
+            Ok(1 << 36),
+            mnemonics_with_prefix_count(&vec!["abandon".to_string(); 20], WordsCount::_24)
+        );
+
+        let mut invalid = mnemonics.clone();
+        invalid[0] = "spend".to_string();
+        assert_eq!(
+            Ok(0),
+            mnemonics_with_prefix_count(&invalid, WordsCount::_12)
+        );
+
+        assert_eq!(
+            Err(Bip39Error::TooManyCombinations(128)),
+            mnemonics_with_prefix_count(&[], WordsCount::_12)
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(15)),
+            mnemonics_with_prefix_count(&vec!["abandon".to_string(); 15], WordsCount::_12)
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidWord("slick".to_string())),
+            mnemonics_with_prefix_count(&["slick".to_string()], WordsCount::_12)
+        );
+    }
+
+    #[test_env_log::test]
+    fn translate_mnemonic_keeps_entropy() {
+        let mnemonic = generate_mnemonics(WordsCount::_12, &DummyEntropy::default()).unwrap();
+
+        assert_eq!(
+            Ok(mnemonic.clone()),
+            translate_mnemonic(&mnemonic, Language::English)
+        );
+
+        #[cfg(not(feature = "japanese"))]
+        assert_eq!(
+            Err(Bip39Error::LanguageNotEnabled(Language::Japanese)),
+            translate_mnemonic(&mnemonic, Language::Japanese)
+        );
+
+        #[cfg(feature = "japanese")]
+        {
+            let japanese = translate_mnemonic(&mnemonic, Language::Japanese).unwrap();
+
+            assert_eq!(Language::Japanese, japanese.language());
+            assert_ne!(mnemonic.words(), japanese.words());
+            assert_eq!(mnemonic.entropy(), japanese.entropy());
+            assert_eq!(
+                Ok(mnemonic),
+                translate_mnemonic(&japanese, Language::English)
+            );
+        }
+    }
+
+    #[cfg(feature = "spanish")]
+    #[test_env_log::test]
+    fn translate_mnemonic_from_spanish() {
+        // "ábaco ... abierto", entropy of "abandon ... about"
+        let mut indices = vec![0; 11];
+        indices.push(3);
+        let spanish = Mnemonic::from_indices(indices, Language::Spanish).unwrap();
+
+        let english = translate_mnemonic(&spanish, Language::English).unwrap();
+        assert_eq!(Language::English, english.language());
+        assert_eq!(crate::bip39::abandon_about(), english);
+        assert_eq!(Ok(spanish), translate_mnemonic(&english, Language::Spanish));
+    }
+
+    #[test_env_log::test]
+    fn valid_entropy_completions_works() {
+        // Entropy of "abandon ... about", checksum 0011
+        let entropy = "0".repeat(128);
+
+        assert_eq!(Ok(1), valid_entropy_completions(&entropy, WordsCount::_12));
+        assert_eq!(
+            Ok(1),
+            valid_entropy_completions(&format!("{}0011", entropy), WordsCount::_12)
+        );
+        assert_eq!(
+            Ok(1),
+            valid_entropy_completions(&format!("{}00", entropy), WordsCount::_12)
+        );
+        assert_eq!(
+            Ok(0),
+            valid_entropy_completions(&format!("{}01", entropy), WordsCount::_12)
+        );
+        assert_eq!(
+            Ok(1 << 7),
+            valid_entropy_completions(&entropy[..121], WordsCount::_12)
+        );
+
+        assert_eq!(
+            Err(Bip39Error::TooManyCombinations(128)),
+            valid_entropy_completions("", WordsCount::_12)
+        );
+        assert!(matches!(
+            valid_entropy_completions("012", WordsCount::_12),
+            Err(Bip39Error::InvalidEntropy(_))
+        ));
+        assert!(matches!(
+            valid_entropy_completions(&"0".repeat(133), WordsCount::_12),
+            Err(Bip39Error::InvalidEntropy(_))
+        ));
+    }
+
+    #[test_env_log::test]
+    fn read_wordlist_rejects_invalid_utf8() {
+        let wordlist: &[u8] = b"abandon\nability\nab\xffle\n";
+
+        match read_wordlist(wordlist) {
+            Err(Bip39Error::InvalidWordlist { line, .. }) => assert_eq!(3, line),
+            other => panic!("Expected InvalidWordlist, got {:?}", other),
+        }
+
+        let wordlist: &[u8] = b"abandon\nability\n";
+        assert_eq!(
+            Ok(vec!["abandon".to_string(), "ability".to_string()]),
+            read_wordlist(wordlist)
+        );
+    }
+
+    #[test_env_log::test]
+    fn read_wordlist_skips_empty_lines() {
+        let wordlist: &[u8] = b"abandon\n\nability\n\n";
+        assert_eq!(
+            Ok(vec!["abandon".to_string(), "ability".to_string()]),
+            read_wordlist(wordlist)
+        );
+
+        let words = get_dictionary();
+        assert!(words.iter().all(|word| !word.is_empty()));
+        assert_eq!(WORDLIST_SIZE, words.len());
+    }
+
     #[test_env_log::test]
     #[ignore]
     fn test_vector() {
@@ -388,13 +2322,14 @@ mod tests {
 
             let word_count: WordsCount = WordsCount::try_from(mnemonics.len()).unwrap();
 
-            assert_eq!(mnemonics, generate_mnemonics(word_count, &ent));
+            let mnemonic = generate_mnemonics(word_count, &ent).unwrap();
+            assert_eq!(mnemonics, mnemonic.words());
 
             assert_eq!(is_checksum_valid(&mnemonics), Ok(true));
 
             assert_eq!(
-                Ok(hex::decode(&test.seed).unwrap()),
-                generate_master_seed_with_password(&mnemonics, "TREZOR")
+                hex::decode(&test.seed).unwrap(),
+                generate_master_seed_with_password(&mnemonic, &Passphrase::new("TREZOR"))
             );
         }
     }
@@ -0,0 +1,509 @@
+use super::{
+    is_checksum_valid, is_indices_checksum_valid, recover_missing_words, split_phrase,
+    valid_final_words, word_index, Bip39Error, Language, Mnemonic, WordsCount,
+};
+use std::convert::TryFrom;
+use std::fmt;
+use unicode_normalization::UnicodeNormalization;
+
+/// How forgiving `parse_mnemonics` is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseMode {
+    /// Only canonical form is accepted: NFKD, lower case, words separated by single space.
+    /// Use it to verify that stored data is canonical.
+    Strict,
+    /// Input is normalized and typos are corrected, like in `parse_and_fix`,
+    /// but the checksum word is never replaced.
+    Lenient,
+}
+
+/// Single change made by `parse_and_fix` to the user input
+#[derive(Debug, PartialEq)]
+pub enum Correction {
+    /// Leading, trailing or repeated whitespace removed
+    WhitespaceNormalized,
+    /// Input was not NFKD normalized, e.g. full-width letters or precomposed accents
+    Normalized,
+    /// Upper case letters changed to lower case
+    Lowercased,
+    /// Word not found in the dictionary, replaced with the closest one
+    WordCorrected {
+        position: usize,
+        from: String,
+        to: String,
+    },
+    /// Checksum did not match, last word replaced with the closest one giving valid checksum
+    ChecksumWordSuggested { from: String, to: String },
+}
+
+/// Corrections further than that are guesses, not typos
+const MAX_DISTANCE: usize = 2;
+
+/// Levenshtein distance, number of single letter insertions, deletions or substitutions
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + (ca != *cb) as usize;
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Returns dictionary word closest to the given one, if there is exactly one such word
+/// within `MAX_DISTANCE`
+fn closest_word<'a>(word: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let mut best = None;
+    let mut best_distance = MAX_DISTANCE + 1;
+    let mut ambiguous = false;
+
+    for candidate in candidates {
+        let distance = edit_distance(word, candidate);
+
+        if distance < best_distance {
+            best = Some(candidate);
+            best_distance = distance;
+            ambiguous = false;
+        } else if distance == best_distance {
+            ambiguous = true;
+        }
+    }
+
+    if ambiguous {
+        None
+    } else {
+        best
+    }
+}
+
+/// Words of the dictionary within `MAX_DISTANCE` from the given one, closest first,
+/// equally close in dictionary order. Empty if the word is in the dictionary.
+pub fn suggest_words(word: &str) -> Vec<String> {
+    let lang = Language::English;
+    if word_index(word, lang).is_some() {
+        return vec![];
+    }
+
+    let mut suggestions: Vec<(usize, &str)> = lang
+        .words()
+        .map(|candidate| (edit_distance(word, candidate), candidate))
+        .filter(|(distance, _)| *distance <= MAX_DISTANCE)
+        .collect();
+    suggestions.sort_by_key(|(distance, _)| *distance);
+
+    suggestions
+        .into_iter()
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+/// Word of the phrase not found in the dictionary, with ranked suggestions to show the user.
+/// Displayed as "slick → click, sick, slice, stick".
+#[derive(Debug, PartialEq)]
+pub struct WordSuggestion {
+    pub position: usize,
+    pub word: String,
+    pub suggestions: Vec<String>,
+}
+
+impl fmt::Display for WordSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} → {}", self.word, self.suggestions.join(", "))
+    }
+}
+
+/// Suggestions for every word of the phrase not found in the dictionary, see `suggest_words`.
+/// Unlike `parse_and_fix` nothing is replaced, the user picks the right word.
+pub fn suggest_corrections(phrase: &str) -> Vec<WordSuggestion> {
+    phrase
+        .nfkd()
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .enumerate()
+        .filter(|(_, word)| word_index(word, Language::English).is_none())
+        .map(|(position, word)| WordSuggestion {
+            position,
+            word: word.to_string(),
+            suggestions: suggest_words(word),
+        })
+        .collect()
+}
+
+/// Tries to turn messy user input into valid mnemonics, reporting every change made.
+///
+/// Normalizes whitespace, NFKD form and case, corrects words not found in the dictionary
+/// to the closest one, and if checksum still does not match, suggests the last word.
+/// Returns `None` together with corrections made so far if validity cannot be reached,
+/// e.g. typo is ambiguous or words count is wrong.
+///
+/// # Warning
+/// Corrected mnemonics may restore a different wallet than intended, show corrections
+/// to the user, especially `ChecksumWordSuggested`.
+pub fn parse_and_fix(input: &str) -> Result<(Option<Mnemonic>, Vec<Correction>), Bip39Error> {
+    let lang = Language::English;
+    let mut corrections = vec![];
+
+    if input.split_whitespace().collect::<Vec<&str>>().join(" ") != input {
+        corrections.push(Correction::WhitespaceNormalized);
+    }
+
+    let normalized: String = input.nfkd().collect();
+    if normalized != input {
+        corrections.push(Correction::Normalized);
+    }
+    if normalized.to_lowercase() != normalized {
+        corrections.push(Correction::Lowercased);
+    }
+
+    // Same words as `Mnemonic::from_phrase` would see
+    let mut mnemonics = split_phrase(input);
+
+    let mut indices = Vec::with_capacity(mnemonics.len());
+    for (position, word) in mnemonics.iter_mut().enumerate() {
+        if let Some(index) = word_index(word, lang) {
+            indices.push(index as usize);
+            continue;
+        }
+
+        match closest_word(word, lang.words()) {
+            Some(closest) => {
+                corrections.push(Correction::WordCorrected {
+                    position,
+                    from: word.clone(),
+                    to: closest.to_string(),
+                });
+                *word = closest.to_string();
+                indices.push(word_index(closest, lang).unwrap() as usize);
+            }
+            None => return Ok((None, corrections)),
+        }
+    }
+
+    if WordsCount::try_from(mnemonics.len()).is_err() {
+        return Ok((None, corrections));
+    }
+
+    if !is_indices_checksum_valid(&indices) {
+        let last = indices.len() - 1;
+
+        // Every entropy has valid last words, so some is always found,
+        // unlike typos suggestion is made even if it is far away
+        let suggested = valid_final_words(&mnemonics[..last])?
+            .into_iter()
+            .min_by_key(|word| edit_distance(&mnemonics[last], word))
+            .ok_or_else(|| Bip39Error::Internal("No valid last word found".to_string()))?;
+
+        corrections.push(Correction::ChecksumWordSuggested {
+            from: mnemonics[last].clone(),
+            to: suggested.to_string(),
+        });
+        mnemonics[last] = suggested.to_string();
+    }
+
+    Ok((Some(Mnemonic::from_words(mnemonics, lang)?), corrections))
+}
+
+/// Parses mnemonics from user input or storage, see `ParseMode`.
+/// In both modes mnemonics with invalid checksum are rejected.
+pub fn parse_mnemonics(input: &str, mode: ParseMode) -> Result<Mnemonic, Bip39Error> {
+    match mode {
+        ParseMode::Strict => {
+            let canonical: String = input
+                .nfkd()
+                .collect::<String>()
+                .to_lowercase()
+                .split_whitespace()
+                .collect::<Vec<&str>>()
+                .join(" ");
+
+            if canonical != input {
+                return Err(Bip39Error::NonCanonical(
+                    "expected lower case NFKD words separated by single space".to_string(),
+                ));
+            }
+
+            Mnemonic::from_words(
+                input.split(' ').map(String::from).collect(),
+                Language::English,
+            )
+        }
+        ParseMode::Lenient => {
+            let (fixed, corrections) = parse_and_fix(&input.nfkd().collect::<String>())?;
+
+            let checksum_fixed = corrections
+                .iter()
+                .any(|correction| matches!(correction, Correction::ChecksumWordSuggested { .. }));
+
+            match fixed {
+                Some(_) if checksum_fixed => Err(Bip39Error::InvalidChecksum),
+                Some(fixed) => Ok(fixed),
+                None => {
+                    // Report what exactly is wrong, without corrections
+                    let mnemonics: Vec<String> =
+                        input.split_whitespace().map(str::to_lowercase).collect();
+                    is_checksum_valid(&mnemonics)?;
+                    Err(Bip39Error::InvalidChecksum)
+                }
+            }
+        }
+    }
+}
+
+/// Returns canonical form of valid mnemonics: NFKD, lower case, words separated by single
+/// space, the one accepted by `ParseMode::Strict`. Store that one if the phrase is later
+/// compared byte for byte. Unlike `parse_and_fix` words are never corrected.
+pub fn canonicalize(phrase: &str) -> Result<String, Bip39Error> {
+    let mnemonics = split_phrase(phrase);
+
+    if !is_checksum_valid(&mnemonics)? {
+        return Err(Bip39Error::InvalidChecksum);
+    }
+
+    Ok(mnemonics.join(" "))
+}
+
+/// Marks unknown word in the phrase given to `recover_phrase`
+pub const UNKNOWN_WORD: &str = "?";
+
+/// Recovers phrase with one or two unknown words marked with `?`, e.g. "stick ? blood ...".
+/// Returns every candidate with valid checksum, for one unknown word there are about
+/// 2048 / 2^checksum_bits of them (exactly that for the last word), for two words 2048 times
+/// more. Use `rank_by_root_key`, `rank_by_address` or `rank_by_xpub` to find the one
+/// actually used.
+pub fn recover_phrase(phrase: &str) -> Result<Vec<Mnemonic>, Bip39Error> {
+    let words: Vec<Option<String>> = phrase
+        .nfkd()
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .map(|word| {
+            if word == UNKNOWN_WORD {
+                None
+            } else {
+                Some(word.to_string())
+            }
+        })
+        .collect();
+
+    let total = WordsCount::try_from(words.len())?;
+
+    recover_missing_words(&words, total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::is_checksum_valid;
+
+    const PHRASE: &str = "stick cluster blood sad onion age laptop grab cement unknown yard spend";
+
+    fn mnemonic() -> Mnemonic {
+        PHRASE.parse().unwrap()
+    }
+
+    #[test]
+    fn suggest_words_ranks_closest_first() {
+        let suggestions = suggest_words("slick");
+
+        assert_eq!(&["click", "sick", "slice", "stick"], &suggestions[..4]);
+        assert!(suggestions[4..]
+            .iter()
+            .all(|word| edit_distance("slick", word) == MAX_DISTANCE));
+
+        assert!(suggest_words("stick").is_empty());
+        assert!(suggest_words("xxxxxxxx").is_empty());
+    }
+
+    #[test]
+    fn suggest_corrections_works() {
+        let phrase = PHRASE.replace("stick", "Slick").replace("yard", "yarrd");
+
+        let suggestions = suggest_corrections(&phrase);
+
+        assert_eq!(2, suggestions.len());
+        assert_eq!(0, suggestions[0].position);
+        assert_eq!("slick", suggestions[0].word);
+        assert!(suggestions[0]
+            .to_string()
+            .starts_with("slick → click, sick, slice, stick"));
+        assert_eq!(10, suggestions[1].position);
+        assert_eq!("yard", suggestions[1].suggestions[0]);
+
+        assert!(suggest_corrections(PHRASE).is_empty());
+    }
+
+    #[test]
+    fn recover_phrase_works() {
+        let phrase = PHRASE.replace("sad", "?");
+
+        let recovered = recover_phrase(&phrase).unwrap();
+        assert!(recovered.contains(&mnemonic()));
+        assert!(recovered
+            .iter()
+            .all(|candidate| is_checksum_valid(candidate.words()) == Ok(true)));
+
+        assert_eq!(
+            Err(Bip39Error::TooManyUnknownWords(3)),
+            recover_phrase("? ? ? cluster blood sad onion age laptop grab cement unknown")
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(11)),
+            recover_phrase("? cluster blood sad onion age laptop grab cement unknown yard")
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidWord("slick".to_string())),
+            recover_phrase(&phrase.replace("stick", "slick"))
+        );
+    }
+
+    #[test]
+    fn edit_distance_works() {
+        assert_eq!(0, edit_distance("blood", "blood"));
+        assert_eq!(1, edit_distance("blod", "blood"));
+        assert_eq!(1, edit_distance("bload", "blood"));
+        assert_eq!(2, edit_distance("lbood", "blood"));
+        assert_eq!(5, edit_distance("", "blood"));
+    }
+
+    #[test]
+    fn valid_input_is_not_changed() {
+        assert_eq!(Ok((Some(mnemonic()), vec![])), parse_and_fix(PHRASE));
+    }
+
+    #[test]
+    fn typo_is_corrected() {
+        let input = "Stick cluster blod sad onion age laptop grab cement unknown  yard spend\n";
+
+        assert_eq!(
+            Ok((
+                Some(mnemonic()),
+                vec![
+                    Correction::WhitespaceNormalized,
+                    Correction::Lowercased,
+                    Correction::WordCorrected {
+                        position: 2,
+                        from: "blod".to_string(),
+                        to: "blood".to_string()
+                    }
+                ]
+            )),
+            parse_and_fix(input)
+        );
+    }
+
+    #[test]
+    fn input_is_nfkd_normalized() {
+        // Full-width letters, as typed with some East Asian keyboards
+        let input = PHRASE.replace("stick", "ｓｔｉｃｋ");
+        assert_eq!(
+            Ok((Some(mnemonic()), vec![Correction::Normalized])),
+            parse_and_fix(&input)
+        );
+
+        let input = PHRASE.replace("stick", "ＳＴＩＣＫ");
+        assert_eq!(
+            Ok((
+                Some(mnemonic()),
+                vec![Correction::Normalized, Correction::Lowercased]
+            )),
+            parse_and_fix(&input)
+        );
+    }
+
+    #[test]
+    fn checksum_word_is_suggested() {
+        let input = PHRASE.replace("stick", "spend");
+
+        let (fixed, corrections) = parse_and_fix(&input).unwrap();
+        let fixed = fixed.unwrap();
+
+        assert_eq!(Some(&"spend".to_string()), fixed.words().first());
+        assert!(matches!(
+            &corrections[..],
+            [Correction::ChecksumWordSuggested { from, .. }] if from == "spend"
+        ));
+        assert_eq!(Ok(true), is_checksum_valid(fixed.words()));
+    }
+
+    #[test]
+    fn strict_mode_requires_canonical_form() {
+        assert_eq!(Ok(mnemonic()), parse_mnemonics(PHRASE, ParseMode::Strict));
+
+        for input in &[
+            PHRASE.replace("stick", "Stick"),
+            PHRASE.replace(" ", "  "),
+            format!("{}\n", PHRASE),
+        ] {
+            assert!(matches!(
+                parse_mnemonics(input, ParseMode::Strict),
+                Err(Bip39Error::NonCanonical(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn lenient_mode_normalizes() {
+        assert_eq!(Ok(mnemonic()), parse_mnemonics(PHRASE, ParseMode::Lenient));
+
+        let input = format!(
+            " {}\n",
+            PHRASE.replace("stick", "Stick").replace("blood", "blod")
+        );
+        assert_eq!(Ok(mnemonic()), parse_mnemonics(&input, ParseMode::Lenient));
+    }
+
+    #[test]
+    fn checksum_is_verified_in_both_modes() {
+        let input = PHRASE.replace("stick", "spend");
+
+        assert_eq!(
+            Err(Bip39Error::InvalidChecksum),
+            parse_mnemonics(&input, ParseMode::Strict)
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidChecksum),
+            parse_mnemonics(&input, ParseMode::Lenient)
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(11)),
+            parse_mnemonics(&PHRASE[6..], ParseMode::Lenient)
+        );
+    }
+
+    #[test]
+    fn canonicalize_works() {
+        let input = format!(
+            "  {}  \n",
+            PHRASE.replace("stick", "Stick").replace(" ", "   ")
+        );
+
+        assert_eq!(Ok(PHRASE.to_string()), canonicalize(&input));
+        assert_eq!(Ok(PHRASE.to_string()), canonicalize(PHRASE));
+
+        assert_eq!(
+            Err(Bip39Error::InvalidChecksum),
+            canonicalize(&PHRASE.replace("stick", "spend"))
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidWord("blod".to_string())),
+            canonicalize(&PHRASE.replace("blood", "blod"))
+        );
+    }
+
+    #[test]
+    fn ambiguous_typo_is_not_fixed() {
+        // "bxxd" is 2 letters away from "bird", "bind", "blood" is 3
+        let input = PHRASE.replace("blood", "bxxd");
+
+        assert_eq!(Ok((None, vec![])), parse_and_fix(&input));
+    }
+}
@@ -0,0 +1,119 @@
+use super::{normalized_sentence, Bip39Error, Mnemonic, Passphrase, Seed, PBKDF2_ROUNDS};
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha512;
+use zeroize::Zeroizing;
+
+/// PBKDF2-HMAC-SHA512 seed derivation, split into steps, so it can be driven
+/// from a background task and abandoned half way, e.g. when the user navigates away.
+///
+/// Seed is 64 bytes, exactly one PBKDF2 block, so the state is just
+/// the last HMAC output and the running XOR of all of them.
+pub struct SeedDeriver {
+    /// HMAC keyed with the mnemonic sentence, cloned for every round
+    mac: Hmac<Sha512>,
+    last: Zeroizing<[u8; 64]>,
+    seed: Zeroizing<[u8; 64]>,
+    rounds_done: u32,
+}
+
+impl SeedDeriver {
+    pub fn new(mnemonic: &Mnemonic, passphrase: &Passphrase) -> Result<Self, Bip39Error> {
+        let sentence = normalized_sentence(mnemonic.words(), mnemonic.language());
+        let mac = Hmac::<Sha512>::new_from_slice(sentence.as_bytes())
+            .map_err(|e| Bip39Error::Internal(e.to_string()))?;
+
+        Ok(SeedDeriver {
+            mac,
+            last: Zeroizing::new([0; 64]),
+            seed: Zeroizing::new([0; 64]),
+            rounds_done: 0,
+        }
+        .first_round(passphrase))
+    }
+
+    /// U1 = HMAC(sentence, salt || block index), block index is always 1
+    fn first_round(mut self, passphrase: &Passphrase) -> Self {
+        let salt = Zeroizing::new(format!("mnemonic{}", passphrase.as_str()));
+
+        let mut mac = self.mac.clone();
+        mac.update(salt.as_bytes());
+        mac.update(&1u32.to_be_bytes());
+        self.last.copy_from_slice(&mac.finalize().into_bytes());
+        self.seed.copy_from_slice(&*self.last);
+        self.rounds_done = 1;
+
+        self
+    }
+
+    /// Advances derivation by at most `rounds` PBKDF2 iterations.
+    /// Returns the seed once all of them are done, `None` otherwise.
+    pub fn step(&mut self, rounds: u32) -> Option<Seed> {
+        let todo = rounds.min(PBKDF2_ROUNDS - self.rounds_done);
+
+        for _ in 0..todo {
+            let mut mac = self.mac.clone();
+            mac.update(&*self.last);
+            self.last.copy_from_slice(&mac.finalize().into_bytes());
+
+            for (seed, last) in self.seed.iter_mut().zip(self.last.iter()) {
+                *seed ^= last;
+            }
+        }
+        self.rounds_done += todo;
+
+        if self.rounds_done == PBKDF2_ROUNDS {
+            Some(self.seed.to_vec())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::generate_master_seed_with_password;
+
+    const PHRASE: &str = "stick cluster blood sad onion age laptop grab cement unknown yard spend";
+
+    fn mnemonic() -> Mnemonic {
+        PHRASE.parse().unwrap()
+    }
+
+    #[test]
+    fn any_chunk_size_gives_known_seed() {
+        let expected = hex::decode(
+            "f3990aab57ffcba134df93414ce4246091a68598c6e06142dd3e62\
+             5990542bcc51f356971e33c98e597dc76590e1fa8b3a2e5e3195b6\
+             41d0ad34ddd5441dd0ec",
+        )
+        .unwrap();
+
+        for &chunk in &[1, 7, 100, 2047, 2048, u32::MAX] {
+            let mut deriver = SeedDeriver::new(&mnemonic(), &Passphrase::empty()).unwrap();
+
+            let seed = loop {
+                if let Some(seed) = deriver.step(chunk) {
+                    break seed;
+                }
+            };
+
+            assert_eq!(expected, seed);
+            // Finished deriver keeps returning the seed
+            assert_eq!(Some(expected.clone()), deriver.step(1));
+        }
+    }
+
+    #[test]
+    fn passphrase_is_used() {
+        let passphrase = Passphrase::new("TREZOR");
+        let mut deriver = SeedDeriver::new(&mnemonic(), &passphrase).unwrap();
+
+        assert_eq!(None, deriver.step(0));
+        assert_eq!(None, deriver.step(2046));
+        assert_eq!(
+            Some(generate_master_seed_with_password(&mnemonic(), &passphrase)),
+            deriver.step(1)
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Detection of Electrum seeds, which are NOT BIP39.
+//! # Resources
+//! https://electrum.readthedocs.io/en/latest/seedphrase.html
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha512;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Prefixes of HMAC-SHA512 of the phrase, marking Electrum seed version:
+/// standard, segwit, 2fa, 2fa segwit
+const ELECTRUM_SEED_PREFIXES: [&str; 4] = ["01", "100", "101", "102"];
+
+/// Normalizes phrase the way Electrum does: NFKD, lowercase, no accents, single spaces.
+/// Electrum additionally removes spaces between CJK characters, that is not done here.
+fn normalize(phrase: &str) -> String {
+    let phrase: String = phrase
+        .nfkd()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !is_combining_mark(*c))
+        .collect();
+
+    phrase.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Checks if the phrase is an Electrum seed.
+///
+/// Electrum uses its own seed scheme, importing Electrum seed into BIP39 wallet
+/// silently gives a different (empty) wallet, so user should be warned.
+/// Only new (version 2+) seeds are detected, derivation is not implemented.
+pub fn is_electrum_seed(phrase: &str) -> bool {
+    let mut mac = Hmac::<Sha512>::new_from_slice(b"Seed version").unwrap();
+    mac.update(normalize(phrase).as_bytes());
+    let version = hex::encode(mac.finalize().into_bytes());
+
+    ELECTRUM_SEED_PREFIXES
+        .iter()
+        .any(|prefix| version.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn electrum_seed_is_detected() {
+        // Segwit seeds from Electrum test suite
+        assert!(is_electrum_seed(
+            "wild father tree among universe such mobile favorite target dynamic credit identify"
+        ));
+        assert!(is_electrum_seed(
+            "  Bitter grass shiver impose acquire brush forget axis eager alone wine SILVER\n"
+        ));
+    }
+
+    #[test]
+    fn bip39_mnemonics_are_not_electrum_seed() {
+        assert!(!is_electrum_seed(
+            "stick cluster blood sad onion age laptop grab cement unknown yard spend"
+        ));
+        assert!(!is_electrum_seed(
+            "abandon abandon abandon abandon abandon abandon \
+             abandon abandon abandon abandon abandon about"
+        ));
+    }
+}
@@ -0,0 +1,97 @@
+use super::{valid_final_words, word_index, Bip39Error, Language, Mnemonic, WordsCount};
+
+/// Word by word entry of mnemonics, for recovery UIs. Every word is checked when entered,
+/// once all but the last word are there, `candidates` lists the valid last words.
+pub struct MnemonicEntry {
+    total: WordsCount,
+    words: Vec<String>,
+}
+
+impl MnemonicEntry {
+    pub fn new(total: WordsCount) -> Self {
+        MnemonicEntry {
+            total,
+            words: Vec::with_capacity(total as usize),
+        }
+    }
+
+    /// Appends the next word, fails if it is not in the dictionary or all words are entered
+    pub fn push(&mut self, word: &str) -> Result<(), Bip39Error> {
+        if self.words.len() == self.total as usize {
+            return Err(Bip39Error::InvalidWordsCount(self.words.len() + 1));
+        }
+        if word_index(word, Language::English).is_none() {
+            return Err(Bip39Error::InvalidWord(word.to_string()));
+        }
+
+        self.words.push(word.to_string());
+
+        Ok(())
+    }
+
+    /// Removes the last entered word, e.g. to correct it
+    pub fn pop(&mut self) -> Option<String> {
+        self.words.pop()
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    /// Valid last words once all but the last word are entered, empty otherwise
+    pub fn candidates(&self) -> Vec<&'static str> {
+        if self.words.len() + 1 != self.total as usize {
+            return vec![];
+        }
+
+        valid_final_words(&self.words).unwrap_or_default()
+    }
+
+    /// Validates complete mnemonics
+    pub fn finish(self) -> Result<Mnemonic, Bip39Error> {
+        Mnemonic::from_words(self.words, Language::English)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str = "stick cluster blood sad onion age laptop grab cement unknown yard spend";
+
+    #[test]
+    fn candidates_are_listed_before_last_word() {
+        let mut entry = MnemonicEntry::new(WordsCount::_12);
+        let words: Vec<&str> = PHRASE.split(' ').collect();
+
+        for word in &words[..10] {
+            entry.push(word).unwrap();
+            assert!(entry.candidates().is_empty());
+        }
+        entry.push(words[10]).unwrap();
+
+        let candidates = entry.candidates();
+        assert_eq!(128, candidates.len());
+        assert!(candidates.contains(&"spend"));
+
+        entry.push("spend").unwrap();
+        assert!(entry.candidates().is_empty());
+        assert_eq!(Err(Bip39Error::InvalidWordsCount(13)), entry.push("spend"));
+
+        assert_eq!(PHRASE, entry.finish().unwrap().to_string());
+    }
+
+    #[test]
+    fn invalid_words_are_rejected() {
+        let mut entry = MnemonicEntry::new(WordsCount::_12);
+
+        assert_eq!(
+            Err(Bip39Error::InvalidWord("slick".to_string())),
+            entry.push("slick")
+        );
+        assert!(entry.words().is_empty());
+
+        entry.push("stick").unwrap();
+        assert_eq!(Some("stick".to_string()), entry.pop());
+    }
+}
@@ -0,0 +1,305 @@
+use super::{Bip39Error, Wordlist};
+use serde::Serialize;
+
+/// SHA256 of the official BIP39 English wordlist, `english.txt` has to match it byte for byte
+pub const ENGLISH_WORDLIST_SHA256: [u8; 32] = [
+    0x2f, 0x5e, 0xed, 0x53, 0xa4, 0x72, 0x7b, 0x4b, 0xf8, 0x88, 0x0d, 0x8f, 0x3f, 0x19, 0x9e, 0xfc,
+    0x90, 0xe5, 0x85, 0x03, 0x64, 0x6d, 0x9f, 0xf8, 0xef, 0xf3, 0xa2, 0xed, 0x3b, 0x24, 0xdb, 0xda,
+];
+
+/// SHA256 of the official BIP39 Japanese wordlist
+pub const JAPANESE_WORDLIST_SHA256: [u8; 32] = [
+    0x2e, 0xed, 0x0a, 0xef, 0x49, 0x22, 0x91, 0xe0, 0x61, 0x63, 0x3d, 0x7a, 0xd8, 0x11, 0x7f, 0x1a,
+    0x2b, 0x03, 0xeb, 0x80, 0xa2, 0x9d, 0x0e, 0x4e, 0x31, 0x17, 0xac, 0x25, 0x28, 0xd0, 0x5f, 0xfd,
+];
+
+/// SHA256 of the official BIP39 Korean wordlist
+pub const KOREAN_WORDLIST_SHA256: [u8; 32] = [
+    0x9e, 0x95, 0xf8, 0x6c, 0x16, 0x7d, 0xe8, 0x8f, 0x45, 0x0f, 0x0a, 0xaf, 0x89, 0xe8, 0x7f, 0x66,
+    0x24, 0xa5, 0x7f, 0x97, 0x3c, 0x67, 0xb5, 0x16, 0xe3, 0x38, 0xe8, 0xe8, 0xb8, 0x89, 0x7f, 0x60,
+];
+
+/// SHA256 of the official BIP39 Spanish wordlist
+pub const SPANISH_WORDLIST_SHA256: [u8; 32] = [
+    0x46, 0x84, 0x6a, 0x5a, 0x01, 0x39, 0xd1, 0xe3, 0xcb, 0x77, 0x29, 0x3e, 0x52, 0x1c, 0x28, 0x65,
+    0xf7, 0xbc, 0xdb, 0x82, 0xc4, 0x4e, 0x8d, 0x0a, 0x06, 0xa2, 0xcd, 0x0e, 0xcb, 0xa4, 0x8c, 0x0b,
+];
+
+/// SHA256 of the official BIP39 Chinese Simplified wordlist
+pub const CHINESE_SIMPLIFIED_WORDLIST_SHA256: [u8; 32] = [
+    0x5c, 0x59, 0x42, 0x79, 0x2b, 0xd8, 0x34, 0x0c, 0xb8, 0xb2, 0x7c, 0xd5, 0x92, 0xf1, 0x01, 0x5e,
+    0xdf, 0x56, 0xa8, 0xc5, 0xb2, 0x62, 0x76, 0xee, 0x18, 0xa4, 0x82, 0x42, 0x8e, 0x7c, 0x57, 0x26,
+];
+
+/// SHA256 of the official BIP39 Chinese Traditional wordlist
+pub const CHINESE_TRADITIONAL_WORDLIST_SHA256: [u8; 32] = [
+    0x41, 0x7b, 0x26, 0xb3, 0xd8, 0x50, 0x0a, 0x4a, 0xe3, 0xd5, 0x97, 0x17, 0xd7, 0x01, 0x19, 0x52,
+    0xdb, 0x6f, 0xc2, 0xfb, 0x84, 0xb8, 0x07, 0xf3, 0xf9, 0x4a, 0xc7, 0x34, 0xe8, 0x9c, 0x1b, 0x5f,
+];
+
+/// SHA256 of the official BIP39 French wordlist
+pub const FRENCH_WORDLIST_SHA256: [u8; 32] = [
+    0xeb, 0xc3, 0x95, 0x9a, 0xb7, 0x80, 0x1a, 0x1d, 0xf6, 0xba, 0xc4, 0xfa, 0x7d, 0x97, 0x06, 0x52,
+    0xf1, 0xdf, 0x76, 0xb6, 0x83, 0xcd, 0x2f, 0x40, 0x03, 0xc9, 0x41, 0xc6, 0x3d, 0x51, 0x7e, 0x59,
+];
+
+/// SHA256 of the official BIP39 Italian wordlist
+pub const ITALIAN_WORDLIST_SHA256: [u8; 32] = [
+    0xd3, 0x92, 0xc4, 0x9f, 0xdb, 0x70, 0x0a, 0x24, 0xcd, 0x1f, 0xce, 0xb2, 0x37, 0xc1, 0xf6, 0x5d,
+    0xcc, 0x12, 0x8f, 0x6b, 0x34, 0xa8, 0xaa, 0xcb, 0x58, 0xb5, 0x93, 0x84, 0xb5, 0xc6, 0x48, 0xc2,
+];
+
+/// SHA256 of the official BIP39 Czech wordlist
+pub const CZECH_WORDLIST_SHA256: [u8; 32] = [
+    0x7e, 0x80, 0xe1, 0x61, 0xc3, 0xe9, 0x3d, 0x95, 0x54, 0xc2, 0xef, 0xb7, 0x8d, 0x4e, 0x3c, 0xeb,
+    0xf8, 0xfc, 0x72, 0x7e, 0x9c, 0x52, 0xe0, 0x3b, 0x83, 0xb9, 0x44, 0x06, 0xbd, 0xcc, 0x95, 0xfc,
+];
+
+/// SHA256 of the official BIP39 Portuguese wordlist
+pub const PORTUGUESE_WORDLIST_SHA256: [u8; 32] = [
+    0x26, 0x85, 0xe9, 0xc1, 0x94, 0xc8, 0x2a, 0xe6, 0x7e, 0x10, 0xba, 0x59, 0xd9, 0xea, 0x53, 0x45,
+    0xa2, 0x3d, 0xc0, 0x93, 0xe9, 0x22, 0x76, 0xfc, 0x53, 0x61, 0xf6, 0x66, 0x7d, 0x79, 0xcd, 0x3f,
+];
+
+/// Language of the BIP39 dictionary. All variants always exist, so API is the same
+/// regardless of features, but only English dictionary is always compiled in, others
+/// need their cargo feature, e.g. `japanese`. Otherwise `Bip39Error::LanguageNotEnabled`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum Language {
+    English,
+    Japanese,
+    Korean,
+    Spanish,
+    ChineseSimplified,
+    ChineseTraditional,
+    French,
+    Italian,
+    Czech,
+    Portuguese,
+}
+
+impl Language {
+    /// All languages, in the order of `id`, including the ones not compiled in
+    pub fn all() -> impl Iterator<Item = Language> {
+        (0..=u8::MAX).map_while(Language::from_id)
+    }
+
+    /// Stable identifier of the language, e.g. to store it next to encrypted backup.
+    /// Numbering follows BIP85: English=0, Japanese=1, Korean=2, Spanish=3, ...
+    /// Portuguese is not in BIP85, it comes last.
+    pub fn id(&self) -> u8 {
+        match self {
+            Language::English => 0,
+            Language::Japanese => 1,
+            Language::Korean => 2,
+            Language::Spanish => 3,
+            Language::ChineseSimplified => 4,
+            Language::ChineseTraditional => 5,
+            Language::French => 6,
+            Language::Italian => 7,
+            Language::Czech => 8,
+            Language::Portuguese => 9,
+        }
+    }
+
+    /// Inverse of `id`
+    pub fn from_id(id: u8) -> Option<Language> {
+        match id {
+            0 => Some(Language::English),
+            1 => Some(Language::Japanese),
+            2 => Some(Language::Korean),
+            3 => Some(Language::Spanish),
+            4 => Some(Language::ChineseSimplified),
+            5 => Some(Language::ChineseTraditional),
+            6 => Some(Language::French),
+            7 => Some(Language::Italian),
+            8 => Some(Language::Czech),
+            9 => Some(Language::Portuguese),
+            _ => None,
+        }
+    }
+
+    /// Enabled languages whose dictionary contains all the words, in the order of `id`.
+    /// More than one if the words are shared by dictionaries, e.g. "animal" is both English
+    /// and French, checksum usually tells which one it is. Words have to be NFKD normalized.
+    pub fn detect(words: &[&str]) -> Vec<Language> {
+        if words.is_empty() {
+            return vec![];
+        }
+
+        Language::all()
+            .filter(|lang| lang.ensure_enabled().is_ok())
+            .filter(|lang| words.iter().all(|word| word_index(word, *lang).is_some()))
+            .collect()
+    }
+
+    /// Separator of words in the mnemonic sentence, the one fed to PBKDF2
+    pub fn separator(&self) -> &'static str {
+        match self {
+            // Ideographic space
+            Language::Japanese => "\u{3000}",
+            _ => " ",
+        }
+    }
+
+    /// Returns error if dictionary of the language is not compiled in
+    pub fn ensure_enabled(&self) -> Result<(), Bip39Error> {
+        self.wordlist().map(|_| ())
+    }
+
+    /// Dictionary embedded in the binary, one word per line, NFKD normalized
+    fn wordlist(&self) -> Result<&'static str, Bip39Error> {
+        match self {
+            Language::English => Ok(include_str!("english.txt")),
+            #[cfg(feature = "japanese")]
+            Language::Japanese => Ok(include_str!("japanese.txt")),
+            #[cfg(feature = "korean")]
+            Language::Korean => Ok(include_str!("korean.txt")),
+            #[cfg(feature = "spanish")]
+            Language::Spanish => Ok(include_str!("spanish.txt")),
+            #[cfg(feature = "chinese-simplified")]
+            Language::ChineseSimplified => Ok(include_str!("chinese_simplified.txt")),
+            #[cfg(feature = "chinese-traditional")]
+            Language::ChineseTraditional => Ok(include_str!("chinese_traditional.txt")),
+            #[cfg(feature = "french")]
+            Language::French => Ok(include_str!("french.txt")),
+            #[cfg(feature = "italian")]
+            Language::Italian => Ok(include_str!("italian.txt")),
+            #[cfg(feature = "czech")]
+            Language::Czech => Ok(include_str!("czech.txt")),
+            #[cfg(feature = "portuguese")]
+            Language::Portuguese => Ok(include_str!("portuguese.txt")),
+            // Unreachable with all languages enabled
+            #[allow(unreachable_patterns)]
+            _ => Err(Bip39Error::LanguageNotEnabled(*self)),
+        }
+    }
+
+    /// Words of the dictionary, empty lines are skipped so they don't shift the indices.
+    /// Empty if the language is not enabled.
+    pub(crate) fn words(&self) -> impl Iterator<Item = &'static str> {
+        self.wordlist()
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| !line.is_empty())
+    }
+}
+
+/// Returns position of the word in the dictionary, 0 - 2047.
+/// `None` also if the language is not enabled, see `Language::ensure_enabled`.
+pub fn word_index(word: &str, lang: Language) -> Option<u16> {
+    Wordlist::for_language(lang).ok()?.index_of(word)
+}
+
+/// Returns word placed in the dictionary on the given position
+pub fn word_for_index(index: u16, lang: Language) -> Option<&'static str> {
+    Wordlist::for_language(lang).ok()?.word(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+    use unicode_normalization::UnicodeNormalization;
+
+    #[test]
+    fn english_wordlist_hash_matches() {
+        let hash = Sha256::digest(Language::English.wordlist().unwrap().as_bytes());
+
+        assert_eq!(ENGLISH_WORDLIST_SHA256, hash.as_slice());
+    }
+
+    #[test]
+    fn enabled_wordlists_hashes_match() {
+        let hashes = [
+            (Language::English, ENGLISH_WORDLIST_SHA256),
+            (Language::Japanese, JAPANESE_WORDLIST_SHA256),
+            (Language::Korean, KOREAN_WORDLIST_SHA256),
+            (Language::Spanish, SPANISH_WORDLIST_SHA256),
+            (
+                Language::ChineseSimplified,
+                CHINESE_SIMPLIFIED_WORDLIST_SHA256,
+            ),
+            (
+                Language::ChineseTraditional,
+                CHINESE_TRADITIONAL_WORDLIST_SHA256,
+            ),
+            (Language::French, FRENCH_WORDLIST_SHA256),
+            (Language::Italian, ITALIAN_WORDLIST_SHA256),
+            (Language::Czech, CZECH_WORDLIST_SHA256),
+            (Language::Portuguese, PORTUGUESE_WORDLIST_SHA256),
+        ];
+        assert_eq!(Language::all().count(), hashes.len());
+
+        for (lang, expected) in &hashes {
+            if let Ok(wordlist) = lang.wordlist() {
+                let hash = Sha256::digest(wordlist.as_bytes());
+
+                assert_eq!(expected, hash.as_slice(), "{:?}", lang);
+                assert_eq!(2048, lang.words().count(), "{:?}", lang);
+                assert!(lang.words().all(|word| word.nfkd().eq(word.chars())));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "japanese")]
+    fn japanese_words_are_found() {
+        assert_eq!(Some(0), word_index("あいこくしん", Language::Japanese));
+        assert_eq!(Some("われる"), word_for_index(2047, Language::Japanese));
+    }
+
+    #[test]
+    #[cfg(not(feature = "japanese"))]
+    fn disabled_language_is_reported() {
+        assert_eq!(
+            Err(Bip39Error::LanguageNotEnabled(Language::Japanese)),
+            Language::Japanese.ensure_enabled()
+        );
+        assert_eq!(None, word_index("あいこくしん", Language::Japanese));
+        assert_eq!(None, word_for_index(0, Language::Japanese));
+    }
+
+    #[test]
+    fn word_index_works() {
+        assert_eq!(Some(0), word_index("abandon", Language::English));
+        assert_eq!(Some(2047), word_index("zoo", Language::English));
+        assert_eq!(None, word_index("slick", Language::English));
+    }
+
+    #[test]
+    fn word_for_index_works() {
+        assert_eq!(Some("abandon"), word_for_index(0, Language::English));
+        assert_eq!(Some("zoo"), word_for_index(2047, Language::English));
+        assert_eq!(None, word_for_index(2048, Language::English));
+    }
+
+    #[test]
+    fn detect_works() {
+        assert_eq!(vec![Language::English], Language::detect(&["stick", "zoo"]));
+        assert!(Language::detect(&["stick", "slick"]).is_empty());
+        assert!(Language::detect(&[]).is_empty());
+
+        #[cfg(not(feature = "french"))]
+        assert_eq!(vec![Language::English], Language::detect(&["animal"]));
+
+        #[cfg(feature = "french")]
+        assert_eq!(
+            vec![Language::English, Language::French],
+            Language::detect(&["animal"])
+        );
+    }
+
+    #[test]
+    fn language_id_round_trips() {
+        assert_eq!(0, Language::English.id());
+        assert_eq!(10, Language::all().count());
+
+        for id in 0..=u8::MAX {
+            if let Some(lang) = Language::from_id(id) {
+                assert_eq!(id, lang.id());
+                assert_eq!(Some(lang), Language::from_id(lang.id()));
+            }
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use super::{
+    generate_master_seed_with_password, indices_to_entropy, is_indices_checksum_valid, Bip39Error,
+    Language, Passphrase, Seed, Wordlist, WordsCount,
+};
+use crate::redacted::Redacted;
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter::Map;
+use std::slice::Iter;
+use std::str::FromStr;
+use unicode_normalization::UnicodeNormalization;
+
+/// Mnemonics known to be valid: words from the dictionary of the language,
+/// valid words count and checksum. Checked once on construction, so the rest of
+/// the code does not have to, unlike a plain list of words.
+///
+/// Words are hidden from `Debug`, `Display` prints the phrase.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mnemonic {
+    words: Redacted<Vec<String>>,
+    language: Language,
+    /// Position of every word in the dictionary
+    indices: Redacted<Vec<usize>>,
+}
+
+impl Mnemonic {
+    /// Single entry point for whatever the user typed: splits on any whitespace,
+    /// normalizes (NFKD, lower case) and validates the phrase.
+    /// Words can be abbreviated to their first `UNIQUE_PREFIX_LEN` letters, as on some backups.
+    /// Words count, unknown word and checksum are reported as distinct errors.
+    ///
+    /// Language is detected among the enabled ones, see `Language::detect`. If words fit
+    /// more than one dictionary, the first one with valid checksum is taken. Abbreviated
+    /// or unknown words are looked up in English.
+    pub fn from_phrase(phrase: &str) -> Result<Self, Bip39Error> {
+        let words = split_phrase(phrase);
+        let languages = Language::detect(&words.iter().map(String::as_str).collect::<Vec<_>>());
+
+        let mut first_error = None;
+        for language in languages {
+            match Mnemonic::from_words(words.clone(), language) {
+                Ok(mnemonic) => return Ok(mnemonic),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Mnemonic::from_phrase_in(phrase, Language::English),
+        }
+    }
+
+    /// Same as `from_phrase`, with the dictionary of the given language
+    pub fn from_phrase_in(phrase: &str, language: Language) -> Result<Self, Bip39Error> {
+        let wordlist = Wordlist::for_language(language)?;
+
+        let words = split_phrase(phrase)
+            .into_iter()
+            .map(|word| wordlist.expand(&word).map(String::from).unwrap_or(word))
+            .collect();
+
+        Mnemonic::from_words(words, language)
+    }
+
+    /// Takes already split words, they have to be NFKD normalized, as in the dictionary
+    pub fn from_words(words: Vec<String>, language: Language) -> Result<Self, Bip39Error> {
+        let wordlist = Wordlist::for_language(language)?;
+        WordsCount::try_from(words.len())?;
+
+        let indices = words
+            .iter()
+            .map(|word| {
+                wordlist
+                    .index_of(word)
+                    .map(usize::from)
+                    .ok_or_else(|| Bip39Error::InvalidWord(word.clone()))
+            })
+            .collect::<Result<Vec<usize>, Bip39Error>>()?;
+
+        if !is_indices_checksum_valid(&indices) {
+            return Err(Bip39Error::InvalidChecksum);
+        }
+
+        Ok(Mnemonic {
+            words: Redacted::new(words),
+            language,
+            indices: Redacted::new(indices),
+        })
+    }
+
+    /// Words of already known positions in the dictionary, e.g. freshly generated
+    pub(super) fn from_indices(
+        indices: Vec<usize>,
+        language: Language,
+    ) -> Result<Self, Bip39Error> {
+        let wordlist = Wordlist::for_language(language)?;
+        WordsCount::try_from(indices.len())?;
+
+        if let Some(&index) = indices.iter().find(|&&index| index >= wordlist.len()) {
+            return Err(Bip39Error::InvalidWordIndex(index));
+        }
+        if !is_indices_checksum_valid(&indices) {
+            return Err(Bip39Error::InvalidChecksum);
+        }
+
+        let words = indices
+            .iter()
+            .filter_map(|&index| wordlist.word(index as u16))
+            .map(String::from)
+            .collect();
+
+        Ok(Mnemonic {
+            words: Redacted::new(words),
+            language,
+            indices: Redacted::new(indices),
+        })
+    }
+
+    /// Words, e.g. for functions taking `&[String]`
+    pub fn words(&self) -> &[String] {
+        self.words.expose()
+    }
+
+    /// Iterates over the words
+    pub fn iter(&self) -> Map<Iter<'_, String>, fn(&String) -> &str> {
+        self.words.expose().iter().map(String::as_str)
+    }
+
+    pub fn word_count(&self) -> WordsCount {
+        // Checked on construction
+        WordsCount::try_from(self.len()).expect("Mnemonic has invalid words count")
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.expose().len()
+    }
+
+    /// Always false, there are at least 12 words, for completeness with `len`
+    pub fn is_empty(&self) -> bool {
+        self.words.expose().is_empty()
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// Seed of the mnemonic, see `generate_master_seed_with_password`
+    pub fn to_seed(&self, passphrase: &Passphrase) -> Seed {
+        generate_master_seed_with_password(self, passphrase)
+    }
+
+    /// Position of every word in the dictionary
+    pub(super) fn indices(&self) -> &[usize] {
+        self.indices.expose()
+    }
+
+    /// Entropy the mnemonic encodes, see `mnemonic_to_entropy`
+    pub fn entropy(&self) -> Vec<u8> {
+        indices_to_entropy(self.indices.expose()).0
+    }
+
+    /// Reports pairs of positions (counted from 0) holding the same word.
+    ///
+    /// Repeated words are allowed by BIP39, but often mean a transcription error,
+    /// so UI can ask the user to double-check them. Not an error on its own.
+    pub fn repeated_words(&self) -> Vec<(usize, usize, String)> {
+        let mut repeated = vec![];
+
+        for (i, first) in self.iter().enumerate() {
+            for (j, second) in self.iter().enumerate().skip(i + 1) {
+                if first == second {
+                    repeated.push((i, j, first.to_string()));
+                }
+            }
+        }
+
+        repeated
+    }
+
+    /// Returns a note if the mnemonic is shorter than recommended for high-value storage,
+    /// `None` otherwise
+    pub fn strength_warning(&self) -> Option<String> {
+        match self.word_count() {
+            word_count @ WordsCount::_12 | word_count @ WordsCount::_15 => Some(format!(
+                "{}-word mnemonics carry {} bits of entropy, \
+                 consider 24 words (256 bits) for high-value storage",
+                word_count as usize,
+                word_count.keyspace_bits()
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl AsRef<[String]> for Mnemonic {
+    fn as_ref(&self) -> &[String] {
+        self.words()
+    }
+}
+
+impl<'a> IntoIterator for &'a Mnemonic {
+    type Item = &'a str;
+    type IntoIter = Map<Iter<'a, String>, fn(&String) -> &str>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Phrase, words joined with the separator of the language
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.words.expose().join(self.language.separator()))
+    }
+}
+
+impl FromStr for Mnemonic {
+    type Err = Bip39Error;
+
+    fn from_str(phrase: &str) -> Result<Self, Self::Err> {
+        Mnemonic::from_phrase(phrase)
+    }
+}
+
+/// "abandon abandon ... about", mnemonic of all zero entropy used by most test vectors
+#[cfg(test)]
+pub(crate) fn abandon_about() -> Mnemonic {
+    format!("{}about", "abandon ".repeat(11)).parse().unwrap()
+}
+
+/// Splits whatever the user typed into NFKD normalized, lower case words
+pub(super) fn split_phrase(phrase: &str) -> Vec<String> {
+    phrase
+        .nfkd()
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::mnemonic_to_entropy;
+
+    const PHRASE: &str = "stick cluster blood sad onion age laptop grab cement unknown yard spend";
+
+    #[test]
+    fn from_phrase_normalizes_input() {
+        let mnemonic = Mnemonic::from_phrase(PHRASE).unwrap();
+        assert_eq!(12, mnemonic.words().len());
+        assert_eq!("stick", mnemonic.words()[0]);
+
+        let typed = format!(
+            "\t{}\n",
+            PHRASE.replace("stick", "STICK").replace(" ", "  ")
+        );
+        assert_eq!(Ok(mnemonic.clone()), Mnemonic::from_phrase(&typed));
+        assert_eq!(Ok(mnemonic), typed.parse());
+    }
+
+    #[test]
+    fn from_phrase_accepts_abbreviated_words() {
+        let abbreviated = "stic clus bloo sad onio age lapt grab ceme unkn yard spen";
+
+        assert_eq!(
+            Mnemonic::from_phrase(PHRASE),
+            Mnemonic::from_phrase(abbreviated)
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidWord("sti".to_string())),
+            Mnemonic::from_phrase(&PHRASE.replace("stick", "sti"))
+        );
+    }
+
+    #[test]
+    fn from_phrase_reports_what_is_wrong() {
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(11)),
+            Mnemonic::from_phrase(&PHRASE[6..])
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidWord("slick".to_string())),
+            Mnemonic::from_phrase(&PHRASE.replace("stick", "slick"))
+        );
+        assert_eq!(
+            Err(Bip39Error::InvalidChecksum),
+            Mnemonic::from_phrase(&PHRASE.replace("stick", "spend"))
+        );
+    }
+
+    #[test]
+    fn accessors_work() {
+        let mnemonic: Mnemonic = PHRASE.parse().unwrap();
+
+        assert_eq!(PHRASE, mnemonic.to_string());
+        assert_eq!(WordsCount::_12, mnemonic.word_count());
+        assert_eq!(12, mnemonic.len());
+        assert!(!mnemonic.is_empty());
+        assert_eq!(Language::English, mnemonic.language());
+        assert_eq!(
+            mnemonic_to_entropy(mnemonic.words()).unwrap(),
+            mnemonic.entropy()
+        );
+
+        assert_eq!(
+            hex::decode(
+                "f3990aab57ffcba134df93414ce4246091a68598c6e06142dd3e62\
+                 5990542bcc51f356971e33c98e597dc76590e1fa8b3a2e5e3195b6\
+                 41d0ad34ddd5441dd0ec"
+            )
+            .unwrap(),
+            mnemonic.to_seed(&Passphrase::empty())
+        );
+
+        let mut words = vec![];
+        for word in &mnemonic {
+            words.push(word);
+        }
+        assert_eq!(PHRASE.split(' ').collect::<Vec<_>>(), words);
+    }
+
+    #[test]
+    #[cfg(feature = "japanese")]
+    fn japanese_is_joined_with_ideographic_space() {
+        let translated = crate::bip39::translate_mnemonic(
+            &Mnemonic::from_phrase(PHRASE).unwrap(),
+            Language::Japanese,
+        )
+        .unwrap();
+        let phrase = translated.words().join("\u{3000}");
+
+        let mnemonic = Mnemonic::from_phrase_in(&phrase, Language::Japanese).unwrap();
+        assert_eq!(phrase, mnemonic.to_string());
+        assert_eq!(translated, mnemonic);
+        assert_eq!(Ok(mnemonic.clone()), Mnemonic::from_phrase(&phrase));
+        assert_eq!(
+            Mnemonic::from_phrase(PHRASE).unwrap().entropy(),
+            mnemonic.entropy()
+        );
+    }
+
+    #[test]
+    fn from_phrase_detects_language() {
+        assert_eq!(
+            Language::English,
+            Mnemonic::from_phrase(PHRASE).unwrap().language()
+        );
+
+        #[cfg(feature = "french")]
+        {
+            let translated = crate::bip39::translate_mnemonic(
+                &Mnemonic::from_phrase(PHRASE).unwrap(),
+                Language::French,
+            )
+            .unwrap();
+
+            let mnemonic = Mnemonic::from_phrase(&translated.to_string()).unwrap();
+            assert_eq!(Language::French, mnemonic.language());
+            assert_eq!(translated, mnemonic);
+        }
+    }
+
+    #[test]
+    fn repeated_words_are_reported() {
+        let mnemonic: Mnemonic =
+            "legal winner thank year wave sausage worth useful legal winner thank yellow"
+                .parse()
+                .unwrap();
+
+        assert_eq!(
+            vec![
+                (0, 8, "legal".to_string()),
+                (1, 9, "winner".to_string()),
+                (2, 10, "thank".to_string())
+            ],
+            mnemonic.repeated_words()
+        );
+
+        assert!(Mnemonic::from_phrase(PHRASE)
+            .unwrap()
+            .repeated_words()
+            .is_empty());
+    }
+
+    #[test]
+    fn strength_warning_for_short_mnemonics() {
+        assert!(abandon_about().strength_warning().is_some());
+
+        let mnemonic: Mnemonic = format!("{}art", "abandon ".repeat(23)).parse().unwrap();
+        assert_eq!(None, mnemonic.strength_warning());
+    }
+
+    #[test]
+    fn words_are_not_debug_printed() {
+        let mnemonic = Mnemonic::from_phrase(PHRASE).unwrap();
+
+        assert!(!format!("{:?}", mnemonic).contains("stick"));
+    }
+}
@@ -1,2 +1,18 @@
 mod bip39;
-pub(crate) use bip39::*;
\ No newline at end of file
+mod correction;
+mod deriver;
+mod electrum;
+mod entry;
+mod language;
+mod mnemonic;
+mod passphrase;
+mod wordlist;
+pub use bip39::*;
+pub use correction::*;
+pub use deriver::*;
+pub use electrum::*;
+pub use entry::*;
+pub use language::*;
+pub use mnemonic::*;
+pub use passphrase::*;
+pub use wordlist::*;
@@ -0,0 +1,126 @@
+use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroizing;
+
+/// Passphrase protecting the seed ("password" in BIP39).
+///
+/// NFKD normalized on construction, as BIP39 requires,
+/// memory is zeroed when passphrase is dropped.
+pub struct Passphrase(Zeroizing<String>);
+
+impl Passphrase {
+    pub fn new(passphrase: &str) -> Self {
+        Passphrase(Zeroizing::new(passphrase.nfkd().collect()))
+    }
+
+    /// No passphrase, seed is derived from mnemonics only
+    pub fn empty() -> Self {
+        Passphrase(Zeroizing::new(String::new()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Passphrase {
+    fn from(passphrase: &str) -> Self {
+        Passphrase::new(passphrase)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StrengthLabel {
+    Weak,
+    Moderate,
+    Strong,
+}
+
+/// Rough estimate of the passphrase strength, see `passphrase_strength`
+#[derive(Debug, PartialEq)]
+pub struct PassphraseStrength {
+    /// Number of characters
+    pub length: usize,
+    /// Size of the alphabet the characters seem to be drawn from
+    pub charset_size: usize,
+    /// length * log2(charset_size), upper bound, assumes characters are chosen at random
+    pub entropy_bits: f64,
+    pub label: StrengthLabel,
+}
+
+/// Estimates strength of the passphrase, basing on its length and the character classes used.
+///
+/// It does not change seed derivation in any way, any passphrase is accepted.
+/// Estimate is optimistic - dictionary words score as high as random characters.
+pub fn passphrase_strength(passphrase: &str) -> PassphraseStrength {
+    let has = |pred: fn(&char) -> bool| passphrase.chars().any(|c| pred(&c));
+
+    let mut charset_size = 0;
+    if has(char::is_ascii_lowercase) {
+        charset_size += 26;
+    }
+    if has(char::is_ascii_uppercase) {
+        charset_size += 26;
+    }
+    if has(char::is_ascii_digit) {
+        charset_size += 10;
+    }
+    // Punctuation and space
+    if has(|c| c.is_ascii_punctuation() || *c == ' ') {
+        charset_size += 33;
+    }
+    // Whatever else, accented letters, other scripts
+    if has(|c| !c.is_ascii()) {
+        charset_size += 100;
+    }
+
+    let length = passphrase.chars().count();
+    let entropy_bits = if charset_size > 0 {
+        length as f64 * (charset_size as f64).log2()
+    } else {
+        0.0
+    };
+
+    let label = if entropy_bits < 40.0 {
+        StrengthLabel::Weak
+    } else if entropy_bits < 80.0 {
+        StrengthLabel::Moderate
+    } else {
+        StrengthLabel::Strong
+    };
+
+    PassphraseStrength {
+        length,
+        charset_size,
+        entropy_bits,
+        label,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passphrase_is_nfkd_normalized() {
+        // Precomposed é becomes e followed by combining acute accent
+        assert_eq!("cafe\u{301}", Passphrase::new("caf\u{e9}").as_str());
+        assert_eq!("", Passphrase::empty().as_str());
+    }
+
+    #[test]
+    fn passphrase_strength_works() {
+        let strength = passphrase_strength("");
+        assert_eq!(0, strength.length);
+        assert_eq!(StrengthLabel::Weak, strength.label);
+
+        assert_eq!(StrengthLabel::Weak, passphrase_strength("TREZOR").label);
+        assert_eq!(
+            StrengthLabel::Moderate,
+            passphrase_strength("correcthorse").label
+        );
+
+        let strength = passphrase_strength("h7$Kp2!qZr9@Lm4#Xw");
+        assert_eq!(95, strength.charset_size);
+        assert_eq!(StrengthLabel::Strong, strength.label);
+    }
+}
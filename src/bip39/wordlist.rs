@@ -0,0 +1,317 @@
+use super::{Bip39Error, Language, WORDLIST_SIZE};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use unicode_normalization::UnicodeNormalization;
+
+/// Words are abbreviated to that many letters on backups, BIP39 guarantees
+/// they are unique in the English dictionary
+pub const UNIQUE_PREFIX_LEN: usize = 4;
+
+/// BIP39 dictionary, words in the order of their indices.
+///
+/// Dictionaries of the languages are embedded in the binary, so no file I/O
+/// is needed at runtime.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wordlist<'a> {
+    words: Vec<&'a str>,
+    /// Same words sorted, for prefix lookup, not every dictionary is sorted
+    sorted: Vec<&'a str>,
+    /// Position in the dictionary of every sorted word
+    positions: Vec<u16>,
+}
+
+/// Dictionaries of the languages, built on first use, one per `Language::id`
+static WORDLISTS: [OnceLock<Wordlist<'static>>; 10] = [const { OnceLock::new() }; 10];
+
+impl Wordlist<'static> {
+    /// Dictionary of the language, fails if the language is not compiled in.
+    /// Built once and shared, lookups do not have to cache it.
+    pub fn for_language(lang: Language) -> Result<&'static Self, Bip39Error> {
+        lang.ensure_enabled()?;
+
+        Ok(WORDLISTS[usize::from(lang.id())].get_or_init(|| Wordlist::new(lang.words().collect())))
+    }
+
+    /// English dictionary, always compiled in
+    pub fn english() -> &'static Self {
+        Wordlist::for_language(Language::English).expect("English is always compiled in")
+    }
+}
+
+impl<'a> Wordlist<'a> {
+    fn new(words: Vec<&'a str>) -> Self {
+        let mut positions: Vec<u16> = (0..words.len() as u16).collect();
+        positions.sort_unstable_by_key(|&position| words[position as usize]);
+        let sorted = positions
+            .iter()
+            .map(|&position| words[position as usize])
+            .collect();
+
+        Wordlist {
+            words,
+            sorted,
+            positions,
+        }
+    }
+
+    /// Non-standard dictionary supplied by the user, e.g. internal one of the company.
+    /// Checked to hold the same properties as the official ones: exactly 2048 words,
+    /// no whitespace, NFKD normalized, unique, and unique in the first `UNIQUE_PREFIX_LEN`
+    /// letters. Errors point to the offending line, counted from 1,
+    /// except `InvalidWordlistLength`.
+    ///
+    /// Mnemonics made with it are not restorable by other BIP39 wallets.
+    pub fn custom(words: Vec<&'a str>) -> Result<Self, Bip39Error> {
+        if words.len() != WORDLIST_SIZE {
+            return Err(Bip39Error::InvalidWordlistLength {
+                expected: WORDLIST_SIZE,
+                found: words.len(),
+            });
+        }
+
+        let mut seen = HashMap::new();
+        let mut prefixes = HashMap::new();
+
+        for (i, word) in words.iter().enumerate() {
+            let line = i + 1;
+            let invalid = |reason: String| Err(Bip39Error::InvalidWordlist { line, reason });
+
+            if word.is_empty() || word.contains(char::is_whitespace) {
+                return invalid(format!("{:?} is empty or has whitespace", word));
+            }
+            if !word.nfkd().eq(word.chars()) {
+                return invalid(format!("{:?} is not NFKD normalized", word));
+            }
+            if let Some(other) = seen.insert(*word, line) {
+                return invalid(format!("{:?} is already on line {}", word, other));
+            }
+
+            let prefix: String = word.chars().take(UNIQUE_PREFIX_LEN).collect();
+            if let Some(other) = prefixes.insert(prefix, line) {
+                return invalid(format!(
+                    "{:?} starts with the same {} letters as line {}",
+                    word, UNIQUE_PREFIX_LEN, other
+                ));
+            }
+        }
+
+        Ok(Wordlist::new(words))
+    }
+
+    /// Word on the given position, 0 - 2047
+    pub fn word(&self, index: u16) -> Option<&'a str> {
+        self.words.get(index as usize).copied()
+    }
+
+    /// Position of the word in the dictionary, binary search of the sorted words
+    pub fn index_of(&self, word: &str) -> Option<u16> {
+        self.sorted
+            .binary_search(&word)
+            .ok()
+            .map(|i| self.positions[i])
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.index_of(word).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Words in the order of their indices
+    pub fn iter(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.words.iter().copied()
+    }
+
+    /// Words starting with the prefix, sorted, for autocompletion.
+    /// For `UNIQUE_PREFIX_LEN` letters there is at most one English word.
+    pub fn complete(&self, prefix: &str) -> &[&'a str] {
+        let start = self.sorted.partition_point(|&word| word < prefix);
+        let len = self.sorted[start..].partition_point(|word| word.starts_with(prefix));
+
+        &self.sorted[start..start + len]
+    }
+
+    /// Dictionary word for the one typed by the user: either the word itself,
+    /// or the only word it is abbreviation of, at least `UNIQUE_PREFIX_LEN` letters long
+    pub fn expand(&self, word: &str) -> Option<&'a str> {
+        if let Some(index) = self.index_of(word) {
+            return self.word(index);
+        }
+
+        match self.complete(word) {
+            [only] if word.chars().count() >= UNIQUE_PREFIX_LEN => Some(only),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::{
+        generate_master_seed_with, generate_mnemonics_with, is_checksum_valid_with, read_wordlist,
+        recover_missing_words_with, Passphrase, WordsCount,
+    };
+    use crate::entropy::BasicEntropy;
+
+    /// Three letter words "aaa", "aab", ..., valid custom dictionary
+    fn custom_words() -> Vec<String> {
+        (0..WORDLIST_SIZE)
+            .map(|i| {
+                [i / 26 / 26, i / 26 % 26, i % 26]
+                    .iter()
+                    .map(|&letter| (b'a' + letter as u8) as char)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn english_wordlist_is_embedded() {
+        let wordlist = Wordlist::english();
+
+        assert_eq!(WORDLIST_SIZE, wordlist.len());
+        assert_eq!(Some("abandon"), wordlist.word(0));
+        assert_eq!(Some("zoo"), wordlist.word(2047));
+        assert_eq!(None, wordlist.word(2048));
+        assert_eq!(Some(1709), wordlist.index_of("stick"));
+        assert!(!wordlist.contains("slick"));
+
+        let from_file = read_wordlist(include_str!("english.txt").as_bytes()).unwrap();
+        assert!(wordlist.iter().eq(from_file.iter().map(String::as_str)));
+    }
+
+    #[test]
+    fn complete_works() {
+        let wordlist = Wordlist::english();
+
+        assert_eq!(&["stick"], wordlist.complete("stic"));
+        assert_eq!(&["stick", "still", "sting"], wordlist.complete("sti"));
+        assert_eq!(&["zebra", "zero", "zone", "zoo"], wordlist.complete("z"));
+        assert!(wordlist.complete("stix").is_empty());
+        assert!(wordlist.complete("zzz").is_empty());
+        assert_eq!(WORDLIST_SIZE, wordlist.complete("").len());
+
+        // Guaranteed by BIP39
+        for word in wordlist
+            .iter()
+            .filter(|word| word.len() >= UNIQUE_PREFIX_LEN)
+        {
+            assert_eq!(&[word], wordlist.complete(&word[..UNIQUE_PREFIX_LEN]));
+        }
+    }
+
+    #[test]
+    fn expand_works() {
+        let wordlist = Wordlist::english();
+
+        assert_eq!(Some("stick"), wordlist.expand("stick"));
+        assert_eq!(Some("stick"), wordlist.expand("stic"));
+        assert_eq!(Some("zoo"), wordlist.expand("zoo"));
+        // Too short to be an abbreviation, even if unique
+        assert_eq!(None, wordlist.expand("zeb"));
+        assert_eq!(None, wordlist.expand("sti"));
+        assert_eq!(None, wordlist.expand("stickk"));
+    }
+
+    #[test]
+    fn custom_wordlist_is_validated() {
+        let words = custom_words();
+        let mut words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+        let wordlist = Wordlist::custom(words.clone()).unwrap();
+        assert_eq!(Some("aaa"), wordlist.word(0));
+        assert_eq!(Some(27), wordlist.index_of("abb"));
+
+        let line_of = |words: Vec<&str>| match Wordlist::custom(words) {
+            Err(Bip39Error::InvalidWordlist { line, .. }) => line,
+            other => panic!("Expected InvalidWordlist, got {:?}", other),
+        };
+
+        assert_eq!(
+            Err(Bip39Error::InvalidWordlistLength {
+                expected: 2048,
+                found: 2047
+            }),
+            Wordlist::custom(words[1..].to_vec())
+        );
+
+        words[10] = "aaa";
+        assert_eq!(11, line_of(words.clone()));
+
+        words[10] = "e\u{301}t\u{e9}";
+        assert_eq!(11, line_of(words.clone()));
+
+        words[10] = "a b";
+        assert_eq!(11, line_of(words.clone()));
+
+        words[10] = "stick";
+        words[11] = "sticky";
+        assert_eq!(12, line_of(words));
+    }
+
+    #[test]
+    fn custom_wordlist_generates_and_recovers() {
+        let words = custom_words();
+        let wordlist = Wordlist::custom(words.iter().map(String::as_str).collect()).unwrap();
+
+        let mnemonics = generate_mnemonics_with(WordsCount::_12, &BasicEntropy, &wordlist).unwrap();
+        assert!(mnemonics.iter().all(|word| wordlist.contains(word)));
+        assert_eq!(Ok(true), is_checksum_valid_with(&mnemonics, &wordlist));
+        assert!(generate_master_seed_with(&mnemonics, &wordlist, &Passphrase::empty()).is_ok());
+        assert_eq!(
+            Err(Bip39Error::InvalidWordsCount(11)),
+            generate_master_seed_with(&mnemonics[1..], &wordlist, &Passphrase::empty())
+        );
+
+        let mut partial: Vec<Option<String>> = mnemonics.iter().cloned().map(Some).collect();
+        partial[11] = None;
+        let recovered = recover_missing_words_with(&partial, WordsCount::_12, &wordlist).unwrap();
+        assert_eq!(128, recovered.len());
+        assert!(recovered.contains(&mnemonics));
+    }
+
+    #[test]
+    fn index_of_unsorted_dictionary() {
+        let words = custom_words();
+        let mut words: Vec<&str> = words.iter().map(String::as_str).collect();
+        words.swap(0, 2047);
+
+        let wordlist = Wordlist::custom(words).unwrap();
+        assert_eq!(Some(0), wordlist.index_of("dat"));
+        assert_eq!(Some(2047), wordlist.index_of("aaa"));
+        assert_eq!(Some(1), wordlist.index_of("aab"));
+        assert_eq!(None, wordlist.index_of("zzz"));
+    }
+
+    #[test]
+    fn wordlist_for_language_works() {
+        assert_eq!(
+            Ok(Wordlist::english()),
+            Wordlist::for_language(Language::English)
+        );
+        // Built once
+        assert!(std::ptr::eq(
+            Wordlist::english(),
+            Wordlist::for_language(Language::English).unwrap()
+        ));
+
+        #[cfg(not(feature = "japanese"))]
+        assert_eq!(
+            Err(Bip39Error::LanguageNotEnabled(Language::Japanese)),
+            Wordlist::for_language(Language::Japanese)
+        );
+
+        #[cfg(feature = "japanese")]
+        assert_eq!(
+            WORDLIST_SIZE,
+            Wordlist::for_language(Language::Japanese).unwrap().len()
+        );
+    }
+}
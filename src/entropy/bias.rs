@@ -0,0 +1,89 @@
+/// Chi-square critical value for 8 degrees of freedom (one per bit position)
+/// at 1% significance level
+const CHI_SQUARE_CRITICAL: f64 = 20.090;
+
+/// Result of `entropy_bias_report`
+#[derive(Debug, Clone, PartialEq)]
+pub struct BiasReport {
+    /// Number of bytes analyzed
+    pub samples: usize,
+    /// Fraction of ones on every bit position of the byte, most significant bit first
+    pub bit_frequency: [f64; 8],
+    /// Chi-square statistic of ones counts on the bit positions, 8 degrees of freedom
+    pub chi_square: f64,
+    /// Whether `chi_square` is below the critical value at 1% significance level
+    pub passed: bool,
+}
+
+/// Measures bias of the entropy collected from a custom source, e.g. dice or coin,
+/// before trusting it with real mnemonics. Feed it a large batch, thousands of bytes,
+/// small ones pass too easily. Empty batch never passes.
+pub fn entropy_bias_report(samples: &[u8]) -> BiasReport {
+    let mut ones = [0usize; 8];
+    for byte in samples {
+        for (position, count) in ones.iter_mut().enumerate() {
+            *count += ((byte >> (7 - position)) & 1) as usize;
+        }
+    }
+
+    let mut bit_frequency = [0.0; 8];
+    let mut chi_square = 0.0;
+
+    if !samples.is_empty() {
+        let n = samples.len() as f64;
+        let expected = n / 2.0;
+
+        for (frequency, &count) in bit_frequency.iter_mut().zip(&ones) {
+            let count = count as f64;
+            *frequency = count / n;
+            // Ones and zeros deviate from expected by the same amount
+            chi_square += 2.0 * (count - expected).powi(2) / expected;
+        }
+    }
+
+    BiasReport {
+        samples: samples.len(),
+        bit_frequency,
+        chi_square,
+        passed: !samples.is_empty() && chi_square < CHI_SQUARE_CRITICAL,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{RngCore, SeedableRng};
+
+    #[test]
+    fn mostly_zeros_fail() {
+        let mut samples = vec![0; 4096];
+        samples[..256].copy_from_slice(&(0..=255).collect::<Vec<u8>>());
+
+        let report = entropy_bias_report(&samples);
+
+        assert_eq!(4096, report.samples);
+        assert!(report.bit_frequency.iter().all(|&f| f < 0.1));
+        assert!(!report.passed);
+    }
+
+    #[test]
+    fn balanced_samples_pass() {
+        let samples: Vec<u8> = (0..=255).cycle().take(4096).collect();
+        let report = entropy_bias_report(&samples);
+
+        assert_eq!([0.5; 8], report.bit_frequency);
+        assert_eq!(0.0, report.chi_square);
+        assert!(report.passed);
+
+        let mut samples = vec![0; 4096];
+        StdRng::seed_from_u64(156).fill_bytes(&mut samples);
+
+        assert!(entropy_bias_report(&samples).passed);
+    }
+
+    #[test]
+    fn empty_samples_fail() {
+        assert!(!entropy_bias_report(&[]).passed);
+    }
+}
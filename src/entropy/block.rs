@@ -0,0 +1,78 @@
+use super::EntropySource;
+use crate::bip39::Bip39Error;
+
+/// Adapts sources returning fixed-size blocks, e.g. hardware RNG giving 32 bytes per read,
+/// to requests of any size. Draws blocks until `count` bits are gathered, excess is dropped.
+pub struct BlockEntropy<E: EntropySource> {
+    inner: E,
+    block_bytes: usize,
+}
+
+impl<E: EntropySource> BlockEntropy<E> {
+    pub fn new(inner: E, block_bytes: usize) -> Self {
+        assert!(block_bytes > 0, "Block size has to be positive");
+
+        BlockEntropy { inner, block_bytes }
+    }
+}
+
+impl<E: EntropySource> EntropySource for BlockEntropy<E> {
+    fn get_random_bits(&self, count: usize) -> Vec<u8> {
+        self.try_get_random_bits(count)
+            .expect("Failed to get entropy")
+    }
+
+    fn try_get_random_bits(&self, count: usize) -> Result<Vec<u8>, Bip39Error> {
+        let mut out = Vec::with_capacity(count / 8 + self.block_bytes);
+
+        while out.len() < count / 8 {
+            let block = self.inner.try_get_random_bits(self.block_bytes * 8)?;
+
+            if block.is_empty() {
+                return Err(Bip39Error::EntropyUnavailable(
+                    "Source returned empty block".to_string(),
+                ));
+            }
+            out.extend_from_slice(&block);
+        }
+        out.truncate(count / 8);
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Returns 8 byte blocks filled with consecutive numbers
+    struct CountingBlocks(Cell<u8>);
+
+    impl EntropySource for CountingBlocks {
+        fn get_random_bits(&self, count: usize) -> Vec<u8> {
+            assert_eq!(64, count);
+
+            let block = self.0.get();
+            self.0.set(block + 1);
+            vec![block; 8]
+        }
+    }
+
+    #[test]
+    fn blocks_are_concatenated() {
+        let ent = BlockEntropy::new(CountingBlocks(Cell::new(1)), 8);
+
+        assert_eq!(
+            vec![1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2],
+            ent.get_random_bits(128)
+        );
+    }
+
+    #[test]
+    fn excess_is_trimmed() {
+        let ent = BlockEntropy::new(CountingBlocks(Cell::new(1)), 8);
+
+        assert_eq!(vec![1, 1, 1, 1, 1, 1, 1, 1, 2, 2], ent.get_random_bits(80));
+    }
+}
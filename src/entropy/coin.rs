@@ -0,0 +1,68 @@
+use super::EntropySource;
+use crate::bip39::Bip39Error;
+
+/// Entropy from coin flips entered by the user, 'H'/'1' is 1, 'T'/'0' is 0,
+/// other characters are ignored. Each flip is used directly as a single bit.
+pub struct CoinEntropy {
+    flips: Vec<bool>,
+}
+
+impl CoinEntropy {
+    pub fn new(flips: &str) -> Self {
+        let flips = flips
+            .chars()
+            .filter_map(|flip| match flip {
+                'H' | 'h' | '1' => Some(true),
+                'T' | 't' | '0' => Some(false),
+                _ => None,
+            })
+            .collect();
+
+        CoinEntropy { flips }
+    }
+}
+
+impl EntropySource for CoinEntropy {
+    fn get_random_bits(&self, count: usize) -> Vec<u8> {
+        self.try_get_random_bits(count)
+            .expect("Not enough coin flips")
+    }
+
+    fn try_get_random_bits(&self, count: usize) -> Result<Vec<u8>, Bip39Error> {
+        if self.flips.len() < count {
+            return Err(Bip39Error::EntropyUnavailable(format!(
+                "not enough coin flips, got {}, need {}",
+                self.flips.len(),
+                count
+            )));
+        }
+
+        // Pack flips into bytes, MSB first
+        Ok(self.flips[..count]
+            .chunks(8)
+            .map(|byte| byte.iter().fold(0, |acc, &bit| (acc << 1) | bit as u8))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coin_flips_are_packed_to_bytes() {
+        let ent = CoinEntropy::new("HTTTTTTH TTTTTTTT");
+
+        assert_eq!(vec![0x81, 0x00], ent.get_random_bits(16));
+    }
+
+    #[test]
+    fn not_enough_coin_flips_is_an_error() {
+        assert_eq!(
+            Err(Bip39Error::EntropyUnavailable(
+                "not enough coin flips, got 2, need 8".to_string()
+            )),
+            CoinEntropy::new("HT").try_get_random_bits(8)
+        );
+    }
+}
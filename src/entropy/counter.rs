@@ -0,0 +1,67 @@
+use super::EntropySource;
+use std::cell::Cell;
+
+/// Deterministic entropy for exhaustive tests: big-endian encoding of a counter,
+/// incremented on every call. Padded with leading zeros, or truncated keeping
+/// the least significant bytes, to the requested length.
+///
+/// Never use it to generate real mnemonics.
+pub struct CounterEntropy {
+    counter: Cell<u64>,
+}
+
+impl CounterEntropy {
+    pub fn new(start: u64) -> Self {
+        CounterEntropy {
+            counter: Cell::new(start),
+        }
+    }
+}
+
+impl EntropySource for CounterEntropy {
+    fn get_random_bits(&self, count: usize) -> Vec<u8> {
+        let value = self.counter.get();
+        self.counter.set(value.wrapping_add(1));
+
+        let bytes = value.to_be_bytes();
+        let len = count / 8;
+
+        let mut out = vec![0; len.saturating_sub(bytes.len())];
+        out.extend_from_slice(&bytes[bytes.len().saturating_sub(len)..]);
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::{decompose, generate_mnemonics, WordsCount};
+
+    #[test]
+    fn counter_is_encoded_big_endian() {
+        let ent = CounterEntropy::new(0x0102);
+
+        let mut expected = vec![0; 16];
+        expected[14..].copy_from_slice(&[0x01, 0x02]);
+        assert_eq!(expected, ent.get_random_bits(128));
+
+        expected[15] = 0x03;
+        assert_eq!(expected, ent.get_random_bits(128));
+
+        assert_eq!(vec![0x04], ent.get_random_bits(8));
+    }
+
+    #[test]
+    fn first_256_values_round_trip() {
+        let ent = CounterEntropy::new(0);
+
+        for value in 0..256u64 {
+            let mnemonic = generate_mnemonics(WordsCount::_12, &ent).unwrap();
+
+            let mut expected = vec![0; 8];
+            expected.extend_from_slice(&value.to_be_bytes());
+            assert_eq!(expected, decompose(mnemonic.words()).unwrap().entropy_bytes);
+        }
+    }
+}
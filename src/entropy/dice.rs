@@ -0,0 +1,120 @@
+use super::EntropySource;
+use crate::bip39::Bip39Error;
+use sha2::{Digest, Sha256};
+
+/// Entropy from dice rolls entered by the user, e.g. "3615243...".
+///
+/// Rolls are hashed with SHA256, single roll gives ~2.58 bits, so 50 rolls are needed
+/// for 128 bits, and 100 for 256 bits. Source cannot provide more than 256 bits.
+pub struct DiceEntropy {
+    rolls: String,
+}
+
+impl DiceEntropy {
+    /// Takes rolls as digits 1 - 6, whitespace between them is ignored,
+    /// any other character is rejected
+    pub fn new(rolls: &str) -> Result<Self, Bip39Error> {
+        let rolls: String = rolls.chars().filter(|c| !c.is_whitespace()).collect();
+
+        if let Some(invalid) = rolls.chars().find(|c| !('1'..='6').contains(c)) {
+            return Err(Bip39Error::InvalidEntropy(format!(
+                "dice roll has to be 1 - 6, got {:?}",
+                invalid
+            )));
+        }
+
+        Ok(DiceEntropy { rolls })
+    }
+
+    /// Rolls needed for `count` bits, each roll gives log2(6) bits
+    fn rolls_needed(count: usize) -> usize {
+        (count as f64 / 6f64.log2()).ceil() as usize
+    }
+}
+
+impl EntropySource for DiceEntropy {
+    fn get_random_bits(&self, count: usize) -> Vec<u8> {
+        self.try_get_random_bits(count)
+            .expect("Not enough dice rolls")
+    }
+
+    fn try_get_random_bits(&self, count: usize) -> Result<Vec<u8>, Bip39Error> {
+        if count > 256 {
+            return Err(Bip39Error::EntropyUnavailable(format!(
+                "dice rolls give at most 256 bits, {} requested",
+                count
+            )));
+        }
+
+        let needed = Self::rolls_needed(count);
+        if self.rolls.len() < needed {
+            return Err(Bip39Error::EntropyUnavailable(format!(
+                "not enough dice rolls, got {}, need {} for {} bits",
+                self.rolls.len(),
+                needed,
+                count
+            )));
+        }
+
+        let hash = Sha256::digest(self.rolls.as_bytes());
+
+        Ok(hash[..count / 8].to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROLLS: &str = "12345612345612345612345612345612345612345612345612";
+
+    #[test]
+    fn dice_rolls_are_hashed() {
+        let ent = DiceEntropy::new(&ROLLS.repeat(2)).unwrap();
+
+        let bits = ent.get_random_bits(128);
+        assert_eq!(16, bits.len());
+        assert_eq!(bits, ent.get_random_bits(256)[..16].to_vec());
+        assert_eq!(
+            bits,
+            DiceEntropy::new(&format!("{} \n{}", ROLLS, ROLLS))
+                .unwrap()
+                .get_random_bits(128)
+        );
+    }
+
+    #[test]
+    fn invalid_rolls_are_rejected() {
+        assert!(matches!(
+            DiceEntropy::new("1234567"),
+            Err(Bip39Error::InvalidEntropy(_))
+        ));
+        assert!(matches!(
+            DiceEntropy::new("hello"),
+            Err(Bip39Error::InvalidEntropy(_))
+        ));
+    }
+
+    #[test]
+    fn not_enough_rolls_is_an_error() {
+        assert_eq!(50, DiceEntropy::rolls_needed(128));
+        assert_eq!(100, DiceEntropy::rolls_needed(256));
+
+        let ent = DiceEntropy::new(ROLLS).unwrap();
+        assert_eq!(16, ent.try_get_random_bits(128).unwrap().len());
+        assert!(matches!(
+            ent.try_get_random_bits(256),
+            Err(Bip39Error::EntropyUnavailable(_))
+        ));
+        assert!(matches!(
+            DiceEntropy::new("1").unwrap().try_get_random_bits(128),
+            Err(Bip39Error::EntropyUnavailable(_))
+        ));
+        assert!(matches!(
+            DiceEntropy::new(&ROLLS.repeat(3))
+                .unwrap()
+                .try_get_random_bits(512),
+            Err(Bip39Error::EntropyUnavailable(_))
+        ));
+    }
+}
@@ -0,0 +1,101 @@
+use super::EntropySource;
+use crate::bip39::Bip39Error;
+use hkdf::Hkdf;
+use sha2::{Digest, Sha512};
+use std::io::Read;
+use zeroize::Zeroizing;
+
+/// Entropy derived from the content of a file, e.g. a photo the user keeps:
+/// SHA-512 of all bytes, expanded with HKDF to the requested length.
+///
+/// # Warning
+/// Not a standard way to generate mnemonics. Entropy is only as strong as the file
+/// is unpredictable, anyone with a copy of the file restores the wallet. Every call
+/// returns the same bytes for the same length.
+pub struct FileHashEntropy {
+    hash: Zeroizing<Vec<u8>>,
+}
+
+impl FileHashEntropy {
+    /// Context of the HKDF expansion, so the output differs from other uses of the file hash
+    const INFO: &'static [u8] = b"wallet_rs file hash entropy";
+
+    /// Reads the whole `reader`, e.g. `File`, and hashes it
+    pub fn new<R: Read>(mut reader: R) -> Result<Self, Bip39Error> {
+        let mut hasher = Sha512::new();
+        let mut buffer = Zeroizing::new(vec![0; 8192]);
+
+        loop {
+            let read = reader
+                .read(&mut buffer)
+                .map_err(|e| Bip39Error::EntropyUnavailable(e.to_string()))?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+
+        Ok(FileHashEntropy {
+            hash: Zeroizing::new(hasher.finalize().to_vec()),
+        })
+    }
+}
+
+impl EntropySource for FileHashEntropy {
+    fn get_random_bits(&self, count: usize) -> Vec<u8> {
+        self.try_get_random_bits(count)
+            .expect("Failed to expand file hash")
+    }
+
+    fn try_get_random_bits(&self, count: usize) -> Result<Vec<u8>, Bip39Error> {
+        let mut out = vec![0; count / 8];
+
+        Hkdf::<Sha512>::new(None, &self.hash)
+            .expand(Self::INFO, &mut out)
+            .map_err(|e| Bip39Error::EntropyUnavailable(e.to_string()))?;
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::{generate_mnemonics, WordsCount};
+    use std::io::{self, Cursor};
+
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk on fire"))
+        }
+    }
+
+    #[test]
+    fn same_file_gives_same_mnemonics() {
+        let content: Vec<u8> = (0..20_000u32).map(|i| (i * 7) as u8).collect();
+
+        let a = FileHashEntropy::new(Cursor::new(content.clone())).unwrap();
+        let b = FileHashEntropy::new(Cursor::new(content.clone())).unwrap();
+
+        let mnemonics = generate_mnemonics(WordsCount::_24, &a).unwrap();
+        assert_eq!(
+            Ok(mnemonics.clone()),
+            generate_mnemonics(WordsCount::_24, &b)
+        );
+
+        let mut other = content;
+        other[0] ^= 1;
+        let c = FileHashEntropy::new(Cursor::new(other)).unwrap();
+        assert_ne!(Ok(mnemonics), generate_mnemonics(WordsCount::_24, &c));
+    }
+
+    #[test]
+    fn read_error_is_reported() {
+        assert!(matches!(
+            FileHashEntropy::new(FailingReader),
+            Err(Bip39Error::EntropyUnavailable(_))
+        ));
+    }
+}
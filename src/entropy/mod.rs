@@ -1,11 +1,63 @@
 // use rand::{thread_rng, Rng};
 use rand::prelude::*;
 
+use crate::bip39::Bip39Error;
+
+mod bias;
+mod block;
+#[cfg(feature = "coin")]
+mod coin;
+mod counter;
+#[cfg(feature = "dice")]
+mod dice;
+#[cfg(feature = "file-hash")]
+mod file_hash;
+#[cfg(feature = "os-rng")]
+mod os_rng;
+mod quality;
+#[cfg(feature = "reader")]
+mod reader;
+mod timeout;
+#[cfg(feature = "timing")]
+mod timing;
+
+pub use bias::{entropy_bias_report, BiasReport};
+pub use block::BlockEntropy;
+#[cfg(feature = "coin")]
+pub use coin::CoinEntropy;
+pub use counter::CounterEntropy;
+#[cfg(feature = "dice")]
+pub use dice::DiceEntropy;
+#[cfg(feature = "file-hash")]
+pub use file_hash::FileHashEntropy;
+#[cfg(feature = "os-rng")]
+pub use os_rng::OsEntropy;
+pub use quality::QualityCheckedEntropy;
+#[cfg(feature = "reader")]
+pub use reader::ReaderEntropy;
+pub use timeout::TimeoutEntropy;
+#[cfg(feature = "timing")]
+pub use timing::TimingEntropy;
+
+/// Entropy source used when caller has no preference, e.g. by the CLI.
+/// Selected at compile time: `OsEntropy` with `os-rng` feature, `BasicEntropy` otherwise.
+#[cfg(feature = "os-rng")]
+pub type DefaultEntropy = OsEntropy;
+#[cfg(not(feature = "os-rng"))]
+pub type DefaultEntropy = BasicEntropy;
+
 pub trait EntropySource {
     // TODO docs
     fn get_random_bits(&self, count: usize) -> Vec<u8>;
+
+    /// Same as `get_random_bits`, for sources that can refuse to give entropy,
+    /// mnemonics generation goes through this one
+    fn try_get_random_bits(&self, count: usize) -> Result<Vec<u8>, Bip39Error> {
+        Ok(self.get_random_bits(count))
+    }
 }
 
+#[derive(Default)]
 pub struct BasicEntropy;
 
 impl EntropySource for BasicEntropy {
@@ -18,3 +70,29 @@ impl EntropySource for BasicEntropy {
         out
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip39::{generate_mnemonics, WordsCount};
+    use std::any::type_name;
+
+    #[test]
+    fn default_entropy_generates_mnemonics() {
+        let mnemonic = generate_mnemonics(WordsCount::_12, &DefaultEntropy::default()).unwrap();
+
+        assert_eq!(WordsCount::_12, mnemonic.word_count());
+    }
+
+    #[test]
+    #[cfg(feature = "os-rng")]
+    fn default_entropy_is_os_entropy() {
+        assert_eq!(type_name::<OsEntropy>(), type_name::<DefaultEntropy>());
+    }
+
+    #[test]
+    #[cfg(not(feature = "os-rng"))]
+    fn default_entropy_is_basic_entropy() {
+        assert_eq!(type_name::<BasicEntropy>(), type_name::<DefaultEntropy>());
+    }
+}
@@ -0,0 +1,28 @@
+use super::EntropySource;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+/// Entropy taken directly from the operating system RNG (getrandom, /dev/urandom).
+#[derive(Default)]
+pub struct OsEntropy;
+
+impl EntropySource for OsEntropy {
+    fn get_random_bits(&self, count: usize) -> Vec<u8> {
+        // Count is number of BITS, change to BYTES by / 8
+        let mut out = vec![0; count / 8];
+
+        OsRng.fill_bytes(&mut out);
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn os_entropy_returns_requested_amount() {
+        assert_eq!(32, OsEntropy.get_random_bits(256).len());
+    }
+}
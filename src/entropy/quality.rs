@@ -0,0 +1,123 @@
+use super::EntropySource;
+use crate::bip39::Bip39Error;
+
+/// Wraps any `EntropySource` of questionable quality, like hand-entered dice or
+/// keystroke timing, and rejects bytes that fail simple monobit or runs tests.
+///
+/// It only catches obviously broken input (stuck bits, repeated patterns),
+/// passing the check does not mean the source is any good.
+pub struct QualityCheckedEntropy<E: EntropySource> {
+    inner: E,
+}
+
+impl<E: EntropySource> QualityCheckedEntropy<E> {
+    /// How many standard deviations from the expected value are tolerated
+    const MAX_DEVIATION: f64 = 4.0;
+
+    pub fn new(inner: E) -> Self {
+        QualityCheckedEntropy { inner }
+    }
+
+    fn check(bytes: &[u8]) -> Result<(), Bip39Error> {
+        let n = (bytes.len() * 8) as f64;
+        let bits = || {
+            bytes
+                .iter()
+                .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1))
+        };
+
+        // Monobit: number of ones should be around n / 2, with std deviation sqrt(n) / 2
+        let ones = bytes.iter().map(|byte| byte.count_ones()).sum::<u32>() as f64;
+        if (ones - n / 2.0).abs() > Self::MAX_DEVIATION * n.sqrt() / 2.0 {
+            return Err(Bip39Error::LowQualityEntropy(format!(
+                "monobit test failed, {} ones in {} bits",
+                ones, n
+            )));
+        }
+
+        // Runs: number of bit changes should be around (n - 1) / 2,
+        // with std deviation sqrt(n - 1) / 2
+        let changes = bits().zip(bits().skip(1)).filter(|(a, b)| a != b).count() as f64;
+        if (changes - (n - 1.0) / 2.0).abs() > Self::MAX_DEVIATION * (n - 1.0).sqrt() / 2.0 {
+            return Err(Bip39Error::LowQualityEntropy(format!(
+                "runs test failed, {} runs in {} bits",
+                changes + 1.0,
+                n
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: EntropySource> EntropySource for QualityCheckedEntropy<E> {
+    fn get_random_bits(&self, count: usize) -> Vec<u8> {
+        self.try_get_random_bits(count)
+            .expect("Entropy failed quality check")
+    }
+
+    fn try_get_random_bits(&self, count: usize) -> Result<Vec<u8>, Bip39Error> {
+        let bytes = self.inner.try_get_random_bits(count)?;
+
+        Self::check(&bytes)?;
+
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{RngCore, SeedableRng};
+    use std::cell::RefCell;
+
+    struct FixedEntropy(Vec<u8>);
+
+    impl EntropySource for FixedEntropy {
+        fn get_random_bits(&self, count: usize) -> Vec<u8> {
+            self.0[..count / 8].to_vec()
+        }
+    }
+
+    struct SeededEntropy(RefCell<StdRng>);
+
+    impl EntropySource for SeededEntropy {
+        fn get_random_bits(&self, count: usize) -> Vec<u8> {
+            let mut out = vec![0; count / 8];
+            self.0.borrow_mut().fill_bytes(&mut out);
+            out
+        }
+    }
+
+    #[test]
+    fn all_zero_bytes_fail_quality_check() {
+        let ent = QualityCheckedEntropy::new(FixedEntropy(vec![0; 32]));
+
+        assert!(matches!(
+            ent.try_get_random_bits(256),
+            Err(Bip39Error::LowQualityEntropy(_))
+        ));
+    }
+
+    #[test]
+    fn alternating_bits_fail_runs_test() {
+        // Perfectly balanced, but way too many runs
+        let ent = QualityCheckedEntropy::new(FixedEntropy(vec![0x55; 32]));
+
+        assert!(matches!(
+            ent.try_get_random_bits(256),
+            Err(Bip39Error::LowQualityEntropy(_))
+        ));
+    }
+
+    #[test]
+    fn random_bytes_pass_quality_check() {
+        let ent =
+            QualityCheckedEntropy::new(SeededEntropy(RefCell::new(StdRng::seed_from_u64(156))));
+
+        for _ in 0..100 {
+            assert_eq!(32, ent.try_get_random_bits(256).unwrap().len());
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use super::EntropySource;
+use crate::bip39::Bip39Error;
+use std::cell::RefCell;
+use std::io::Read;
+
+/// Entropy read from any source implementing `Read`, e.g. hardware TRNG device file.
+pub struct ReaderEntropy<R: Read> {
+    // get_random_bits takes &self, reading needs &mut
+    reader: RefCell<R>,
+}
+
+impl<R: Read> ReaderEntropy<R> {
+    pub fn new(reader: R) -> Self {
+        ReaderEntropy {
+            reader: RefCell::new(reader),
+        }
+    }
+}
+
+impl<R: Read> EntropySource for ReaderEntropy<R> {
+    fn get_random_bits(&self, count: usize) -> Vec<u8> {
+        self.try_get_random_bits(count)
+            .expect("Failed to read entropy")
+    }
+
+    fn try_get_random_bits(&self, count: usize) -> Result<Vec<u8>, Bip39Error> {
+        let mut out = vec![0; count / 8];
+
+        self.reader
+            .borrow_mut()
+            .read_exact(&mut out)
+            .map_err(|e| Bip39Error::EntropyUnavailable(e.to_string()))?;
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn reader_entropy_reads_consecutive_bytes() {
+        let ent = ReaderEntropy::new(Cursor::new(vec![1, 2, 3, 4]));
+
+        assert_eq!(vec![1, 2], ent.get_random_bits(16));
+        assert_eq!(vec![3, 4], ent.get_random_bits(16));
+    }
+
+    #[test]
+    fn failed_read_is_an_error() {
+        let ent = ReaderEntropy::new(Cursor::new(vec![1, 2, 3]));
+
+        assert!(matches!(
+            ent.try_get_random_bits(32),
+            Err(Bip39Error::EntropyUnavailable(_))
+        ));
+    }
+}
@@ -0,0 +1,91 @@
+use super::EntropySource;
+use crate::bip39::Bip39Error;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Protects interactive apps from sources that can block, like `/dev/random`:
+/// gathering runs on a separate thread, if it does not finish within `timeout`
+/// `Bip39Error::EntropyTimeout` is returned.
+///
+/// Thread of the timed out request is left running, next request waits for it
+/// to finish first, so it is likely to time out as well.
+pub struct TimeoutEntropy<E: EntropySource + Send + 'static> {
+    // Shared with the gathering thread, which can outlive a single request
+    inner: Arc<Mutex<E>>,
+    timeout: Duration,
+}
+
+impl<E: EntropySource + Send + 'static> TimeoutEntropy<E> {
+    pub fn new(inner: E, timeout: Duration) -> Self {
+        TimeoutEntropy {
+            inner: Arc::new(Mutex::new(inner)),
+            timeout,
+        }
+    }
+}
+
+impl<E: EntropySource + Send + 'static> EntropySource for TimeoutEntropy<E> {
+    fn get_random_bits(&self, count: usize) -> Vec<u8> {
+        self.try_get_random_bits(count)
+            .expect("Failed to get entropy")
+    }
+
+    fn try_get_random_bits(&self, count: usize) -> Result<Vec<u8>, Bip39Error> {
+        let (sender, receiver) = mpsc::channel();
+        let inner = Arc::clone(&self.inner);
+
+        thread::spawn(move || {
+            let result = match inner.lock() {
+                Ok(inner) => inner.try_get_random_bits(count),
+                Err(_) => Err(Bip39Error::EntropyUnavailable(
+                    "Source panicked before".to_string(),
+                )),
+            };
+            // Receiver is gone if request timed out, nobody waits for the result
+            let _ = sender.send(result);
+        });
+
+        match receiver.recv_timeout(self.timeout) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(Bip39Error::EntropyTimeout(self.timeout)),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(Bip39Error::EntropyUnavailable(
+                "Source panicked".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct SlowEntropy(Duration);
+
+    impl EntropySource for SlowEntropy {
+        fn get_random_bits(&self, count: usize) -> Vec<u8> {
+            thread::sleep(self.0);
+            vec![0xab; count / 8]
+        }
+    }
+
+    #[test]
+    fn slow_source_times_out() {
+        let timeout = Duration::from_millis(20);
+        let ent = TimeoutEntropy::new(SlowEntropy(Duration::from_millis(500)), timeout);
+
+        assert_eq!(
+            Err(Bip39Error::EntropyTimeout(timeout)),
+            ent.try_get_random_bits(128)
+        );
+    }
+
+    #[test]
+    fn fast_source_succeeds() {
+        let ent = TimeoutEntropy::new(SlowEntropy(Duration::ZERO), Duration::from_secs(5));
+
+        assert_eq!(Ok(vec![0xab; 16]), ent.try_get_random_bits(128));
+        assert_eq!(Ok(vec![0xab; 32]), ent.try_get_random_bits(256));
+    }
+}
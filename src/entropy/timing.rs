@@ -0,0 +1,62 @@
+use super::EntropySource;
+use crate::bip39::Bip39Error;
+use sha2::{Digest, Sha256};
+use std::time::Instant;
+
+/// Entropy gathered from CPU timing jitter.
+///
+/// Measures duration of short busy loops, and hashes the measurements with SHA256.
+/// Quality depends heavily on the platform, prefer to mix it with other sources.
+/// Source cannot provide more than 256 bits.
+pub struct TimingEntropy;
+
+impl TimingEntropy {
+    const SAMPLES: usize = 4096;
+}
+
+impl EntropySource for TimingEntropy {
+    fn get_random_bits(&self, count: usize) -> Vec<u8> {
+        self.try_get_random_bits(count)
+            .expect("Too many timing entropy bits requested")
+    }
+
+    fn try_get_random_bits(&self, count: usize) -> Result<Vec<u8>, Bip39Error> {
+        if count > 256 {
+            return Err(Bip39Error::EntropyUnavailable(format!(
+                "timing jitter gives at most 256 bits, {} requested",
+                count
+            )));
+        }
+
+        let mut hasher = Sha256::new();
+
+        for i in 0..Self::SAMPLES {
+            let start = Instant::now();
+            // Some work for the timer to measure, black_box so it is not optimized out
+            std::hint::black_box((0..i % 64).fold(0u64, |acc, x| acc.wrapping_mul(31) ^ x as u64));
+            hasher.update(start.elapsed().as_nanos().to_le_bytes());
+        }
+
+        Ok(hasher.finalize()[..count / 8].to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timing_entropy_differs_between_calls() {
+        let ent = TimingEntropy;
+
+        assert_ne!(ent.get_random_bits(128), ent.get_random_bits(128));
+    }
+
+    #[test]
+    fn more_than_256_bits_is_an_error() {
+        assert!(matches!(
+            TimingEntropy.try_get_random_bits(512),
+            Err(Bip39Error::EntropyUnavailable(_))
+        ));
+    }
+}
@@ -0,0 +1,79 @@
+//! Error of the whole wallet, for code using both BIP39 and BIP32 parts
+use crate::bip32::Bip32Error;
+use crate::bip39::Bip39Error;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Either BIP39 or BIP32 error, e.g. invalid path is `WalletError::Bip32(Bip32Error::InvalidPath)`.
+/// Serialized as the wrapped error, there is no extra nesting level.
+#[derive(Error, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum WalletError {
+    #[error(transparent)]
+    Bip39(Bip39Error),
+
+    #[error(transparent)]
+    Bip32(Bip32Error),
+}
+
+impl From<Bip39Error> for WalletError {
+    fn from(e: Bip39Error) -> Self {
+        WalletError::Bip39(e)
+    }
+}
+
+/// BIP39 errors wrapped by BIP32 ones are unwrapped, so they are matched in one place
+impl From<Bip32Error> for WalletError {
+    fn from(e: Bip32Error) -> Self {
+        match e {
+            Bip32Error::Bip39(e) => WalletError::Bip39(e),
+            e => WalletError::Bip32(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bip32::DerivationPath;
+
+    fn parse_path(path: &str) -> Result<DerivationPath, WalletError> {
+        Ok(path.parse()?)
+    }
+
+    #[test]
+    fn path_errors_are_wrapped() {
+        assert_eq!(
+            Err(WalletError::Bip32(Bip32Error::InvalidPath(
+                "m/84x".to_string()
+            ))),
+            parse_path("m/84x")
+        );
+
+        assert_eq!(
+            Err(WalletError::Bip32(Bip32Error::PathIndexTooLarge(
+                "2147483648".to_string()
+            ))),
+            parse_path("m/2147483648")
+        );
+    }
+
+    #[test]
+    fn bip39_errors_are_unwrapped() {
+        assert_eq!(
+            WalletError::Bip39(Bip39Error::InvalidChecksum),
+            WalletError::from(Bip32Error::Bip39(Bip39Error::InvalidChecksum))
+        );
+    }
+
+    #[test]
+    fn serialized_as_wrapped_error() {
+        assert_eq!(
+            serde_json::json!({ "error": "InvalidPath", "details": "m/x" }),
+            serde_json::to_value(WalletError::from(Bip32Error::InvalidPath(
+                "m/x".to_string()
+            )))
+            .unwrap()
+        );
+    }
+}
@@ -0,0 +1,30 @@
+//! Implementation of bitcoin hardware wallet. Currently holds BIP39 - mnemonics and seed generation,
+//! and BIP32 - key derivation, addresses and descriptors.
+//!
+//! # Features
+//! Only `BasicEntropy` (thread local RNG) is always compiled in, other entropy sources
+//! are opt-in, so minimal consumer does not pay for them:
+//! - `dice` - `DiceEntropy`, hash of dice rolls entered by the user
+//! - `coin` - `CoinEntropy`, coin flips used directly as bits
+//! - `os-rng` - `OsEntropy`, operating system RNG
+//! - `reader` - `ReaderEntropy`, bytes read from any `std::io::Read`, e.g. hardware TRNG device
+//! - `timing` - `TimingEntropy`, CPU timing jitter
+//! - `file-hash` - `FileHashEntropy`, hash of a file content, non-standard
+//!
+//! `DefaultEntropy`, used by the CLI, is `OsEntropy` with `os-rng` feature, `BasicEntropy` otherwise.
+//!
+//! Dictionaries other than English are opt-in as well: `japanese`, `korean`, `spanish`,
+//! `chinese-simplified`, `chinese-traditional`, `french`, `italian`, `czech`, `portuguese`,
+//! or all of them with `all-languages`.
+//!
+//! Feature `encrypt` adds `backup` module, mnemonics encrypted with a password.
+//! Feature `taproot` adds BIP86 Taproot addresses of derived keys.
+//! Feature `eth-keystore` adds Ethereum V3 JSON keystore export of derived keys.
+#[cfg(feature = "encrypt")]
+pub mod backup;
+pub mod bip32;
+pub mod bip39;
+pub mod entropy;
+pub mod error;
+pub mod redacted;
+pub mod test_vectors;
@@ -1,11 +1,90 @@
-mod bip39;
-mod entropy;
-use entropy::BasicEntropy;
 use log::{debug, error, info, trace, warn};
+use std::convert::TryFrom;
 use std::io::Write;
 use std::process;
 use std::thread;
-use bip39::WordsCount;
+use structopt::StructOpt;
+use wallet_rs::bip32::{AddressType, DerivationPath, Network, Wallet};
+use wallet_rs::bip39::{self, Bip39Error, Mnemonic, Passphrase, WordsCount};
+use wallet_rs::entropy::DefaultEntropy;
+use wallet_rs::error::WalletError;
+
+#[derive(StructOpt, Debug)]
+#[structopt(about = "BIP39 mnemonics and seed generation")]
+struct Opt {
+    /// Print results and errors as JSON objects, for programs driving the CLI
+    #[structopt(long, global = true)]
+    json: bool,
+
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(StructOpt, Debug)]
+enum Command {
+    /// Generates new mnemonics
+    Generate {
+        /// Number of words: 12, 15, 18, 21 or 24
+        #[structopt(long, default_value = "12")]
+        words: usize,
+
+        /// Prints that many receive addresses of the first account after the mnemonics
+        #[structopt(long, default_value = "0")]
+        show_addresses: usize,
+
+        /// Type of the printed addresses: native, legacy, p2sh or taproot (with `taproot` feature)
+        #[structopt(long = "type", default_value = "native")]
+        address_type: AddressType,
+    },
+    /// Derives seed from mnemonics, prints it hex encoded
+    Seed {
+        /// Mnemonics, prefer --stdin, arguments leak to shell history and process listings
+        mnemonics: Vec<String>,
+
+        /// Passphrase used together with mnemonics given as arguments
+        #[structopt(long, default_value = "")]
+        passphrase: String,
+
+        /// Read mnemonics and passphrase from stdin, input is hidden on a terminal
+        #[structopt(long)]
+        stdin: bool,
+    },
+    /// Derives the key at the path, prints its key origin and address
+    Derive {
+        /// Derivation path, e.g. m/84'/0'/0'/0/0
+        path: DerivationPath,
+
+        /// Mnemonics, prefer --stdin, arguments leak to shell history and process listings
+        mnemonics: Vec<String>,
+
+        /// Passphrase used together with mnemonics given as arguments
+        #[structopt(long, default_value = "")]
+        passphrase: String,
+
+        /// Read mnemonics and passphrase from stdin, input is hidden on a terminal
+        #[structopt(long)]
+        stdin: bool,
+    },
+    /// Validates checksum of the mnemonics
+    Validate {
+        /// Mnemonics, prefer --stdin, arguments leak to shell history and process listings
+        mnemonics: Vec<String>,
+
+        /// Read mnemonics from stdin, input is hidden on a terminal
+        #[structopt(long)]
+        stdin: bool,
+    },
+    /// Lists mnemonics with valid checksum, for the phrase with up to two unknown words
+    /// marked with ?
+    Recover {
+        /// Mnemonics, prefer --stdin, arguments leak to shell history and process listings
+        mnemonics: Vec<String>,
+
+        /// Read mnemonics from stdin, input is hidden on a terminal
+        #[structopt(long)]
+        stdin: bool,
+    },
+}
 
 fn setup_logger() {
     env_logger::builder()
@@ -31,11 +110,161 @@ fn setup_logger() {
     error!("This is an error message.");
 }
 
+/// Prints error and terminates the process
+fn exit_with_error(msg: &str) -> ! {
+    eprintln!("{}", msg);
+    process::exit(1);
+}
+
+/// Prints result of the command and terminates the process on error.
+/// With `json` prints `{"ok":true,"result":...}`, or `{"ok":false,"error":"Variant",...}`
+/// to stdout, so the caller parses only one stream.
+fn report<E: Into<WalletError>>(json: bool, result: Result<String, E>) {
+    match (result.map_err(Into::into), json) {
+        (Ok(result), false) => println!("{}", result),
+        (Err(e), false) => exit_with_error(&e.to_string()),
+        (Ok(result), true) => println!("{}", serde_json::json!({ "ok": true, "result": result })),
+        (Err(e), true) => {
+            let mut output = serde_json::to_value(&e)
+                .unwrap_or_else(|e| exit_with_error(&format!("Failed to serialize error: {}", e)));
+            output["ok"] = false.into();
+            output["message"] = e.to_string().into();
+
+            println!("{}", output);
+            process::exit(1);
+        }
+    }
+}
+
+/// Reads a line from stdin, echo is disabled if stdin is a terminal
+fn read_hidden(prompt: &str) -> String {
+    rpassword::prompt_password_stderr(prompt)
+        .unwrap_or_else(|e| exit_with_error(&format!("Failed to read stdin: {}", e)))
+}
+
+/// Takes mnemonics either from arguments or from stdin
+fn get_mnemonics(args: Vec<String>, stdin: bool) -> Vec<String> {
+    if stdin {
+        read_hidden("Mnemonics: ")
+            .split_whitespace()
+            .map(String::from)
+            .collect()
+    } else {
+        args
+    }
+}
+
+/// Takes passphrase either from the argument or from stdin, after the mnemonics
+fn get_passphrase(arg: String, stdin: bool) -> String {
+    if stdin {
+        read_hidden("Passphrase: ")
+    } else {
+        arg
+    }
+}
+
+/// Key origin of the key at the path, `[fingerprint/path]xpub...`, followed by its address
+/// if the purpose of the path tells the address type. Network is inferred from the path.
+fn derive(phrase: &str, passphrase: &str, path: &DerivationPath) -> Result<String, WalletError> {
+    let wallet = Wallet::new(&Mnemonic::from_phrase(phrase)?, passphrase)?;
+    let network = Network::from_path(path);
+
+    let mut lines = vec![wallet.key_origin(path, network)?];
+    if let Ok(address_type) = AddressType::from_path(path) {
+        lines.push(wallet.derive(path)?.address(address_type, network)?);
+    }
+
+    Ok(lines.join("\n"))
+}
+
+/// Mnemonics followed by `count` receive addresses of the first account, one per line
+fn with_addresses(
+    mnemonic: &Mnemonic,
+    count: usize,
+    address_type: AddressType,
+) -> Result<String, WalletError> {
+    let mut lines = vec![mnemonic.to_string()];
+
+    // Seed derivation takes a while, skipped when there is nothing to show
+    if count > 0 {
+        let chain = address_type.account_path(0, Network::Bitcoin)?.child(0);
+        lines.extend(Wallet::new(mnemonic, "")?.scan_addresses(&chain, count)?);
+    }
+
+    Ok(lines.join("\n"))
+}
+
 fn main() {
     setup_logger();
 
-    let ent = BasicEntropy;
-    let mnemonics = bip39::generate_mnemonics(WordsCount::_12, &ent);
-    let _seed = bip39::generate_master_seed(&mnemonics);
-    let _valid = bip39::is_checksum_valid(&mnemonics);
+    let Opt { json, command } = Opt::from_args();
+
+    match command {
+        Command::Generate {
+            words,
+            show_addresses,
+            address_type,
+        } => {
+            let ent = DefaultEntropy::default();
+            let mnemonics = WordsCount::try_from(words)
+                .and_then(|word_count| bip39::generate_mnemonics(word_count, &ent));
+
+            report(
+                json,
+                mnemonics
+                    .map_err(WalletError::from)
+                    .and_then(|mnemonic| with_addresses(&mnemonic, show_addresses, address_type)),
+            );
+        }
+        Command::Seed {
+            mnemonics,
+            passphrase,
+            stdin,
+        } => {
+            let mnemonics = get_mnemonics(mnemonics, stdin);
+            let passphrase = Passphrase::new(&get_passphrase(passphrase, stdin));
+
+            let seed = Mnemonic::from_phrase(&mnemonics.join(" "))
+                .map(|mnemonic| bip39::generate_master_seed_with_password(&mnemonic, &passphrase));
+
+            report(json, seed.map(hex::encode));
+        }
+        Command::Derive {
+            path,
+            mnemonics,
+            passphrase,
+            stdin,
+        } => {
+            let phrase = get_mnemonics(mnemonics, stdin).join(" ");
+            let passphrase = get_passphrase(passphrase, stdin);
+
+            report(json, derive(&phrase, &passphrase, &path));
+        }
+        Command::Validate { mnemonics, stdin } => {
+            let mnemonics = get_mnemonics(mnemonics, stdin);
+
+            match bip39::is_checksum_valid(&mnemonics) {
+                Ok(true) => report::<Bip39Error>(json, Ok("valid".to_string())),
+                Ok(false) if json => report(json, Err(Bip39Error::InvalidChecksum)),
+                Ok(false) => {
+                    println!("invalid");
+                    process::exit(1);
+                }
+                Err(e) => report(json, Err(e)),
+            }
+        }
+        Command::Recover { mnemonics, stdin } => {
+            let phrase = get_mnemonics(mnemonics, stdin).join(" ");
+
+            let candidates = bip39::recover_phrase(&phrase).map(|candidates| {
+                candidates
+                    .iter()
+                    .map(Mnemonic::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            });
+
+            report(json, candidates);
+        }
+    }
 }
@@ -0,0 +1,55 @@
+//! Wrapper hiding secrets (entropy, seeds, private keys) from Debug and log output
+use std::fmt;
+
+/// Prints "***" instead of the wrapped value, both in `{:?}` and `{}`.
+///
+/// Use it for secret fields in structs deriving Debug, and in log statements.
+/// Wrapped value is accessible only through explicit `expose()`.
+#[derive(Clone, PartialEq)]
+pub struct Redacted<T>(T);
+
+impl<T> Redacted<T> {
+    pub fn new(value: T) -> Self {
+        Redacted(value)
+    }
+
+    /// Gives access to the secret, make sure it does not end up in logs
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> fmt::Debug for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "***")
+    }
+}
+
+impl<T> fmt::Display for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "***")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacted_does_not_print_value() {
+        let secret = Redacted(vec![1, 2, 3]);
+
+        for printed in &[
+            format!("{:?}", secret),
+            format!("{:x?}", secret),
+            format!("{:#?}", secret),
+            format!("{}", secret),
+        ] {
+            assert!(!printed.contains('1'));
+            assert!(!printed.contains('2'));
+            assert!(!printed.contains('3'));
+        }
+
+        assert_eq!(&vec![1, 2, 3], secret.expose());
+    }
+}
@@ -0,0 +1,135 @@
+//! Generation of test vectors, in the same shape as
+//! https://github.com/trezor/python-mnemonic/blob/master/vectors.json
+//! so regression suites can grow their fixtures.
+use crate::bip32::{bip32_root_key, Network};
+use crate::bip39::{
+    generate_master_seed_with_password, generate_mnemonics_from_entropy, Bip39Error, Passphrase,
+};
+use crate::error::WalletError;
+use serde::{Deserialize, Serialize};
+
+/// Single test vector, field names follow vectors.json
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TestVectorEntry {
+    /// Entropy, hex
+    pub ent: String,
+    pub mnemonics: String,
+    /// Seed, hex
+    pub seed: String,
+    /// BIP32 root key, mainnet
+    pub xprv: String,
+}
+
+/// Known answers for `self_test`, subset of vectors.json: entropy, mnemonics, seed, xprv.
+/// Passphrase is "TREZOR".
+const SELF_TEST_VECTORS: [(&str, &str, &str, &str); 3] = [
+    (
+        "00000000000000000000000000000000",
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+        "xprv9s21ZrQH143K3h3fDYiay8mocZ3afhfULfb5GX8kCBdno77K4HiA15Tg23wpbeF1pLfs1c5SPmYHrEpTuuRhxMwvKDwqdKiGJS9XFKzUsAF",
+    ),
+    (
+        "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+        "legal winner thank year wave sausage worth useful legal winner thank year wave sausage worth useful legal will",
+        "f2b94508732bcbacbcc020faefecfc89feafa6649a5491b8c952cede496c214a0c7b3c392d168748f2d4a612bada0753b52a1c7ac53c1e93abd5c6320b9e95dd",
+        "xprv9s21ZrQH143K3Lv9MZLj16np5GzLe7tDKQfVusBni7toqJGcnKRtHSxUwbKUyUWiwpK55g1DUSsw76TF1T93VT4gz4wt5RM23pkaQLnvBh7",
+    ),
+    (
+        "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+        "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad",
+        "xprv9s21ZrQH143K2WFF16X85T2QCpndrGwx6GueB72Zf3AHwHJaknRXNF37ZmDrtHrrLSHvbuRejXcnYxoZKvRquTPyp2JiNG3XcjQyzSEgqCB",
+    ),
+];
+
+/// Runs embedded known-answer tests: entropy to mnemonics, mnemonics to seed, seed to xprv.
+/// Call it on startup, before any real keys are generated, to catch a miscompiled build
+/// or corrupted wordlist.
+pub fn self_test() -> Result<(), WalletError> {
+    for (ent, mnemonics, seed, xprv) in SELF_TEST_VECTORS.iter() {
+        let vector = generate_test_vector(ent, "TREZOR")?;
+
+        let check = |step: &str, expected: &str, got: &str| {
+            if expected == got {
+                Ok(())
+            } else {
+                Err(Bip39Error::SelfTestFailed(format!(
+                    "{} mismatch for entropy {}",
+                    step, ent
+                )))
+            }
+        };
+
+        check("mnemonics", mnemonics, &vector.mnemonics)?;
+        check("seed", seed, &vector.seed)?;
+        check("xprv", xprv, &vector.xprv)?;
+    }
+
+    Ok(())
+}
+
+/// Generates test vector for the given entropy and passphrase
+/// (vectors.json uses "TREZOR" for all of them).
+pub fn generate_test_vector(
+    entropy_hex: &str,
+    passphrase: &str,
+) -> Result<TestVectorEntry, WalletError> {
+    let entropy =
+        hex::decode(entropy_hex).map_err(|e| Bip39Error::InvalidEntropy(e.to_string()))?;
+
+    let mnemonic = generate_mnemonics_from_entropy(&entropy)?;
+    let seed = generate_master_seed_with_password(&mnemonic, &Passphrase::new(passphrase));
+    let xprv = bip32_root_key(&mnemonic, passphrase, Network::Bitcoin)?;
+
+    Ok(TestVectorEntry {
+        ent: hex::encode(entropy),
+        mnemonics: mnemonic.to_string(),
+        seed: hex::encode(seed),
+        xprv,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::BufReader;
+    use std::path::PathBuf;
+
+    #[derive(Deserialize)]
+    struct TestVector {
+        english: Vec<TestVectorEntry>,
+    }
+
+    #[test]
+    fn regenerated_vectors_match_committed_ones() {
+        let mut filename = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        filename.push("src/bip39/vectors.json");
+        let reader = BufReader::new(File::open(filename).unwrap());
+
+        let test_vector: TestVector = serde_json::from_reader(reader).unwrap();
+
+        for expected in test_vector.english {
+            assert_eq!(
+                Ok(&expected),
+                generate_test_vector(&expected.ent, "TREZOR").as_ref()
+            );
+        }
+    }
+
+    #[test]
+    fn self_test_passes() {
+        assert_eq!(Ok(()), self_test());
+    }
+
+    #[test]
+    fn invalid_entropy_is_rejected() {
+        for entropy in &["zz", "00", "0000000000000000000000000000000000"] {
+            assert!(matches!(
+                generate_test_vector(entropy, ""),
+                Err(WalletError::Bip39(Bip39Error::InvalidEntropy(_)))
+            ));
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Compares mnemonics and seeds with the rust-bitcoin bip39 crate. Random entropy one
+//! is slow in debug builds, run it with `cargo test -- --ignored`
+use proptest::prelude::*;
+use std::convert::TryFrom;
+use unicode_normalization::UnicodeNormalization;
+use wallet_rs::bip39::{self, Language, Mnemonic, Passphrase, WordsCount};
+use wallet_rs::entropy::EntropySource;
+
+struct FixedEntropy(Vec<u8>);
+
+impl EntropySource for FixedEntropy {
+    fn get_random_bits(&self, _count: usize) -> Vec<u8> {
+        self.0.clone()
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(100))]
+
+    #[test]
+    #[ignore]
+    fn mnemonics_and_seeds_match_rust_bip39(
+        len in prop::sample::select(vec![16usize, 20, 24, 28, 32]),
+        bytes in prop::collection::vec(any::<u8>(), 32),
+    ) {
+        let entropy = &bytes[..len];
+        let word_count = WordsCount::try_from(len * 8 / 32 * 3).unwrap();
+
+        let mnemonic = bip39::generate_mnemonics(word_count, &FixedEntropy(entropy.to_vec())).unwrap();
+        let reference = rust_bip39::Mnemonic::from_entropy(entropy).unwrap();
+
+        prop_assert_eq!(mnemonic.to_string(), reference.to_string());
+
+        for &passphrase in &["", "TREZOR", "correct horse battery staple"] {
+            let seed =
+                bip39::generate_master_seed_with_password(&mnemonic, &Passphrase::new(passphrase));
+            prop_assert_eq!(seed, reference.to_seed(passphrase).to_vec());
+        }
+    }
+}
+
+/// Languages compiled in, with their counterparts in the reference crate
+fn enabled_languages() -> Vec<(Language, rust_bip39::Language)> {
+    let all = vec![
+        (Language::English, rust_bip39::Language::English),
+        (Language::Japanese, rust_bip39::Language::Japanese),
+        (Language::Korean, rust_bip39::Language::Korean),
+        (Language::Spanish, rust_bip39::Language::Spanish),
+        (
+            Language::ChineseSimplified,
+            rust_bip39::Language::SimplifiedChinese,
+        ),
+        (
+            Language::ChineseTraditional,
+            rust_bip39::Language::TraditionalChinese,
+        ),
+        (Language::French, rust_bip39::Language::French),
+        (Language::Italian, rust_bip39::Language::Italian),
+        (Language::Czech, rust_bip39::Language::Czech),
+        (Language::Portuguese, rust_bip39::Language::Portuguese),
+    ];
+
+    all.into_iter()
+        .filter(|(lang, _)| lang.ensure_enabled().is_ok())
+        .collect()
+}
+
+#[test]
+fn enabled_languages_match_rust_bip39() {
+    for (lang, reference_lang) in enabled_languages() {
+        for entropy in &[vec![0x7f; 16], vec![0x80; 24], vec![0xa5; 32]] {
+            let word_count = WordsCount::try_from(entropy.len() * 8 / 32 * 3).unwrap();
+            let mnemonic =
+                bip39::generate_mnemonics_in(word_count, &FixedEntropy(entropy.clone()), lang)
+                    .unwrap();
+            let reference = rust_bip39::Mnemonic::from_entropy_in(reference_lang, entropy).unwrap();
+
+            assert_eq!(
+                reference.words().collect::<Vec<_>>(),
+                mnemonic.iter().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                Ok(true),
+                bip39::is_checksum_valid_in(mnemonic.words(), lang)
+            );
+
+            // Composed form, as typed on most keyboards, is normalized
+            let composed: Vec<String> = mnemonic.iter().map(|word| word.nfc().collect()).collect();
+            assert_eq!(Ok(true), bip39::is_checksum_valid_in(&composed, lang));
+
+            // Plain space joined words are fine too, NFKD of the ideographic space is space
+            let composed = Mnemonic::from_phrase_in(&composed.join(" "), lang).unwrap();
+            assert_eq!(mnemonic, composed);
+
+            for &passphrase in &["", "TREZOR", "pässwörd"] {
+                let seed = bip39::generate_master_seed_with_password(
+                    &composed,
+                    &Passphrase::new(passphrase),
+                );
+                assert_eq!(reference.to_seed(passphrase).to_vec(), seed, "{:?}", lang);
+            }
+        }
+    }
+}
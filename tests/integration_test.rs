@@ -5,3 +5,184 @@
 // impl EntropySource for DummyEntropy {}
 // #[test]
 // fn accepts_entropy_source() {}
+
+use serde_json::Value;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use wallet_rs::bip32::{AddressType, Network, Wallet};
+use wallet_rs::bip39::Mnemonic;
+
+#[test]
+fn seed_reads_mnemonics_from_stdin() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_wallet_rs"))
+        .args(&["seed", "--stdin"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Mnemonics in the first line, empty passphrase in the second
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"stick cluster blood sad onion age laptop grab cement unknown yard spend\n\n")
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let seed = "f3990aab57ffcba134df93414ce4246091a68598c6e06142dd3e62\
+                5990542bcc51f356971e33c98e597dc76590e1fa8b3a2e5e3195b6\
+                41d0ad34ddd5441dd0ec";
+    assert_eq!(seed, String::from_utf8(output.stdout).unwrap().trim());
+}
+
+#[test]
+fn derive_reads_mnemonics_from_stdin() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_wallet_rs"))
+        .args(&["derive", "m/84'/0'/0'/0/0", "--stdin"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(format!("{}about\n\n", "abandon ".repeat(11)).as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(2, lines.len());
+    assert!(lines[0].starts_with("[73c5da0a/84'/0'/0'/0/0]zpub"));
+    assert_eq!("bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu", lines[1]);
+}
+
+#[test]
+fn derive_rejects_invalid_path() {
+    let mut args = vec!["derive", "m/84x", "abandon"];
+    args.extend(vec!["abandon"; 10]);
+    args.push("about");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wallet_rs"))
+        .args(&args)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Invalid derivation path m/84x"));
+}
+
+#[test]
+fn seed_rejects_invalid_mnemonics() {
+    let mut args = vec!["seed", "--json"];
+    args.extend(vec!["abandon"; 12]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wallet_rs"))
+        .args(&args)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!("InvalidChecksum", json["error"]);
+}
+
+#[test]
+fn validate_reports_json_error() {
+    let mut args = vec!["validate", "--json"];
+    args.extend(vec!["abandon"; 12]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wallet_rs"))
+        .args(&args)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(Value::Bool(false), json["ok"]);
+    assert_eq!("InvalidChecksum", json["error"]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wallet_rs"))
+        .args(&["validate", "--json", "abandon"])
+        .output()
+        .unwrap();
+
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!("InvalidWordsCount", json["error"]);
+    assert_eq!(1, json["details"]);
+}
+
+#[test]
+fn recover_lists_candidates() {
+    let output = Command::new(env!("CARGO_BIN_EXE_wallet_rs"))
+        .args(&[
+            "recover", "stick", "cluster", "blood", "?", "onion", "age", "laptop", "grab",
+            "cement", "unknown", "yard", "spend",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.lines().count() > 1);
+    assert!(stdout
+        .lines()
+        .any(|line| line
+            == "stick cluster blood sad onion age laptop grab cement unknown yard spend"));
+}
+
+#[test]
+fn generate_shows_addresses() {
+    let output = Command::new(env!("CARGO_BIN_EXE_wallet_rs"))
+        .args(&["generate", "--words", "12", "--show-addresses", "3"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(4, lines.len());
+
+    // Entropy is not seeded, addresses are checked against the printed mnemonics
+    let mnemonic: Mnemonic = lines[0].parse().unwrap();
+    assert_eq!(12, mnemonic.len());
+
+    let wallet = Wallet::new(&mnemonic, "").unwrap();
+    for (index, address) in lines[1..].iter().enumerate() {
+        assert_eq!(
+            wallet
+                .receive_address(AddressType::NativeSegwit, Network::Bitcoin, index as u32)
+                .unwrap(),
+            *address
+        );
+    }
+}
+
+#[test]
+fn generate_shows_addresses_of_type() {
+    let output = Command::new(env!("CARGO_BIN_EXE_wallet_rs"))
+        .args(&["generate", "--show-addresses", "2", "--type", "legacy"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(3, stdout.lines().count());
+    assert!(stdout.lines().skip(1).all(|line| line.starts_with('1')));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_wallet_rs"))
+        .args(&["generate", "--show-addresses", "2", "--type", "segwit"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}